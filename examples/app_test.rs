@@ -0,0 +1,34 @@
+use sokol_rust_egui as segui;
+
+#[derive(Default)]
+struct MyApp {
+    bool_value: bool,
+    value: f32,
+}
+
+impl segui::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Hello, App!").show(ctx, |ui| {
+            ui.label("this is a label!");
+            if ui.button("button!").clicked() {
+                self.bool_value = !self.bool_value;
+            }
+            ui.checkbox(&mut self.bool_value, "check it out");
+            ui.add(egui::Slider::new(&mut self.value, 0.0..=1.0).text("value"));
+        });
+    }
+
+    fn on_exit(&mut self) {
+        println!("bye! the value was {}", self.value);
+    }
+}
+
+fn main() {
+    segui::run(
+        MyApp::default(),
+        segui::WindowOptions {
+            title: "sokol-rust-egui app example".to_string(),
+            ..Default::default()
+        },
+    );
+}
@@ -0,0 +1,79 @@
+/*
+    NOTE: Links against the static library from `cargo build --release`, which also contains the
+          sokol implementation, so there is no SOKOL_IMPL here:
+
+          cc examples/c/main.c -Iinclude -I<path to sokol headers> \
+             target/release/libsokol_rust_egui.a -lGL -lX11 -lXi -lXcursor -lm -ldl -lpthread
+*/
+#define SOKOL_GLCORE33
+#include "sokol_gfx.h"
+#include "sokol_app.h"
+#include "sokol_glue.h"
+#include "sokol_log.h"
+#include "segui.h"
+
+static struct {
+    segui_SokolEgui* segui;
+    bool checked;
+    float value;
+    int clicks;
+} state;
+
+static void window_contents(segui_Ui* ui, void* userdata) {
+    (void)userdata;
+    segui_label(ui, "this is a label from C!");
+    if (segui_button(ui, "button!")) {
+        state.clicks += 1;
+    }
+    segui_checkbox(ui, "check it out", &state.checked);
+    segui_slider_float(ui, "value", &state.value, 0.0f, 1.0f);
+}
+
+static void gui(const segui_Context* ctx) {
+    segui_window(ctx, "Hello, C!", window_contents, 0);
+}
+
+static void init(void) {
+    sg_setup(&(sg_desc){
+        .context = sapp_sgcontext(),
+        .logger.func = slog_func,
+    });
+    state.segui = segui_setup(&(segui_Desc){
+        .gui_cb = gui,
+    });
+}
+
+static void frame(void) {
+    segui_frame(state.segui);
+
+    sg_pass_action pass_action = {
+        .colors[0] = { .action = SG_ACTION_CLEAR, .value = { 0.0f, 0.0f, 0.0f, 1.0f } },
+    };
+    sg_begin_default_pass(&pass_action, sapp_width(), sapp_height());
+    segui_draw(state.segui);
+    sg_end_pass();
+    sg_commit();
+}
+
+static void event(const sapp_event* event) {
+    segui_event(state.segui, event);
+}
+
+static void cleanup(void) {
+    segui_shutdown(state.segui);
+    sg_shutdown();
+}
+
+int main(void) {
+    sapp_run(&(sapp_desc){
+        .init_cb = init,
+        .frame_cb = frame,
+        .event_cb = event,
+        .cleanup_cb = cleanup,
+        .width = 800,
+        .height = 600,
+        .window_title = "sokol-rust-egui C example",
+        .logger.func = slog_func,
+    });
+    return 0;
+}
@@ -0,0 +1,84 @@
+use sokol::app as sapp;
+use sokol::gfx as sg;
+use sokol::glue as sglue;
+use sokol::log as slog;
+
+use sokol_rust_egui as segui;
+
+extern "C" fn frame() {
+    segui::frame();
+
+    let mut pass_action = sg::PassAction::default();
+    pass_action.colors[0] = sg::ColorAttachmentAction {
+        action: sg::Action::Clear,
+        value: sg::Color {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        },
+    };
+
+    sg::begin_default_pass(&pass_action, sapp::width(), sapp::height());
+    segui::draw();
+    sg::end_pass();
+    sg::commit();
+}
+
+extern "C" fn event(event: *const sapp::Event) {
+    segui::event(event);
+}
+
+extern "C" fn init() {
+    sg::setup(&sg::Desc {
+        context: sglue::context(),
+        logger: sg::Logger {
+            func: Some(slog::slog_func),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    segui::begin(&segui::Desc::default());
+
+    /*
+        NOTE: The closure owns the state of the ui, no userdata pointer needed
+    */
+    let mut bool_value = false;
+    let mut counter = 0;
+    segui::set_gui(move |ctx| {
+        egui::Window::new("Hello, Closures!").show(ctx, |ui| {
+            ui.label(format!("clicked {counter} times"));
+            if ui.button("button!").clicked() {
+                counter += 1;
+            }
+            ui.checkbox(&mut bool_value, "check it out");
+        });
+    });
+}
+
+extern "C" fn cleanup() {
+    segui::shutdown();
+    sg::shutdown();
+}
+
+fn main() {
+    sapp::run(&sapp::Desc {
+        init_cb: Some(init),
+        frame_cb: Some(frame),
+        cleanup_cb: Some(cleanup),
+        event_cb: Some(event),
+
+        width: 800,
+        height: 600,
+
+        window_title: b"sokol-rust-egui closure example\0".as_ptr() as _,
+
+        logger: sapp::Logger {
+            func: Some(slog::slog_func),
+            ..Default::default()
+        },
+
+        ..Default::default()
+    });
+}
@@ -9,7 +9,7 @@ struct State {
     bool_value: bool,
 }
 
-extern "C" fn egui_frame(ctx: *const egui::Context, userdata: *mut core::ffi::c_void) {
+extern "C-unwind" fn egui_frame(ctx: *const egui::Context, userdata: *mut core::ffi::c_void) {
     let state = unsafe { &mut *(userdata as *mut State) };
     let ctx = unsafe { &*ctx };
 
@@ -0,0 +1,93 @@
+/* machine generated by cbindgen, do not edit */
+
+#ifndef SEGUI_H
+#define SEGUI_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+#include "sokol_gfx.h"
+#include "sokol_app.h"
+
+typedef enum segui_BlendMode {
+  SEGUI_BLEND_MODE_PREMULTIPLIED,
+} segui_BlendMode;
+
+typedef enum segui_InputCapture {
+  SEGUI_INPUT_CAPTURE_WHEN_EGUI_WANTS_INPUT,
+  SEGUI_INPUT_CAPTURE_ALWAYS_FORWARD,
+  SEGUI_INPUT_CAPTURE_OVER_EGUI_AREA,
+} segui_InputCapture;
+
+typedef enum segui_Renderer {
+  SEGUI_RENDERER_GFX,
+#if defined(SEGUI_FEATURE_SGL)
+  SEGUI_RENDERER_SGL,
+#endif
+} segui_Renderer;
+
+typedef struct segui_Context segui_Context;
+
+typedef struct segui_SokolEgui segui_SokolEgui;
+
+typedef struct segui_Ui segui_Ui;
+
+typedef struct segui_PipelineDesc {
+  sg_pixel_format color_format;
+  sg_pixel_format depth_format;
+  int32_t sample_count;
+  segui_BlendMode blend_mode;
+} segui_PipelineDesc;
+
+typedef struct segui_Desc {
+  void (*gui_cb)(const segui_Context*);
+  void (*gui_userdata_cb)(const segui_Context*, void*);
+  void *userdata;
+  segui_PipelineDesc pipeline;
+  segui_Renderer renderer;
+  uintptr_t initial_vertex_capacity;
+  uintptr_t initial_index_capacity;
+  double autosave_interval;
+  uint32_t max_consecutive_panics;
+  double (*time_cb)(void);
+  double (*time_userdata_cb)(void*);
+  uintptr_t max_dropped_file_read_size;
+  segui_InputCapture input_capture;
+  bool consume_events;
+} segui_Desc;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+segui_SokolEgui *segui_setup(const segui_Desc *desc);
+
+void segui_frame(segui_SokolEgui *segui);
+
+void segui_draw(segui_SokolEgui *segui);
+
+bool segui_event(segui_SokolEgui *segui, const sapp_event *event);
+
+void segui_shutdown(segui_SokolEgui *segui);
+
+void segui_set_persistence_path(segui_SokolEgui *segui, const char *path);
+
+bool segui_window(const segui_Context *ctx,
+                  const char *title,
+                  void (*contents)(segui_Ui*, void*),
+                  void *userdata);
+
+void segui_label(segui_Ui *ui, const char *text);
+
+bool segui_button(segui_Ui *ui, const char *text);
+
+bool segui_checkbox(segui_Ui *ui, const char *text, bool *value);
+
+bool segui_slider_float(segui_Ui *ui, const char *text, float *value, float min, float max);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* SEGUI_H */
@@ -0,0 +1,160 @@
+use sokol::app as sapp;
+use sokol::gfx as sg;
+use sokol::glue as sglue;
+use sokol::log as slog;
+use std::ffi::{c_void, CString};
+
+use crate::{Desc, SokolEgui};
+
+pub trait App {
+    fn update(&mut self, ctx: &egui::Context);
+
+    /*
+        NOTE: Called with every event after egui has seen it
+    */
+    fn on_event(&mut self, _event: &sapp::Event, _handled_by_egui: bool) {}
+
+    fn on_exit(&mut self) {}
+
+    fn clear_color(&self) -> sg::Color {
+        sg::Color {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WindowOptions {
+    pub title: String,
+    pub width: i32,
+    pub height: i32,
+    pub sample_count: i32,
+    pub high_dpi: bool,
+    pub fullscreen: bool,
+    pub desc: Desc,
+}
+
+impl Default for WindowOptions {
+    fn default() -> Self {
+        Self {
+            title: "sokol-rust-egui".to_string(),
+            width: 800,
+            height: 600,
+            sample_count: 1,
+            high_dpi: true,
+            fullscreen: false,
+            desc: Desc::default(),
+        }
+    }
+}
+
+struct Runner {
+    app: Box<dyn App>,
+    segui: Option<SokolEgui>,
+    desc: Desc,
+}
+
+/*
+    NOTE: Owns the app until sokol_app calls cleanup, at which point both the app and segui are
+          shut down and dropped. Everything in between runs on the sokol_app thread
+*/
+pub fn run(app: impl App + 'static, options: WindowOptions) {
+    let runner = Box::new(Runner {
+        app: Box::new(app),
+        segui: None,
+        desc: options.desc,
+    });
+
+    let window_title = CString::new(options.title).unwrap_or_default();
+
+    sapp::run(&sapp::Desc {
+        init_userdata_cb: Some(init),
+        frame_userdata_cb: Some(frame),
+        event_userdata_cb: Some(event),
+        cleanup_userdata_cb: Some(cleanup),
+
+        user_data: Box::into_raw(runner) as *mut c_void,
+
+        width: options.width,
+        height: options.height,
+        sample_count: options.sample_count,
+        high_dpi: options.high_dpi,
+        fullscreen: options.fullscreen,
+        window_title: window_title.as_ptr(),
+        enable_clipboard: true,
+        clipboard_size: crate::MIN_CLIPBOARD_SIZE,
+        enable_dragndrop: true,
+        max_dropped_files: 16,
+
+        logger: sapp::Logger {
+            func: Some(slog::slog_func),
+            ..Default::default()
+        },
+
+        ..Default::default()
+    });
+}
+
+fn runner<'a>(userdata: *mut c_void) -> &'a mut Runner {
+    unsafe { &mut *(userdata as *mut Runner) }
+}
+
+extern "C" fn init(userdata: *mut c_void) {
+    let runner = runner(userdata);
+
+    sg::setup(&sg::Desc {
+        context: sglue::context(),
+        logger: sg::Logger {
+            func: Some(slog::slog_func),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    runner.segui = Some(SokolEgui::new(&runner.desc));
+}
+
+extern "C" fn frame(userdata: *mut c_void) {
+    let runner = runner(userdata);
+    let Some(segui) = runner.segui.as_mut() else {
+        return;
+    };
+
+    let app = &mut runner.app;
+    segui.frame_with(|ctx| app.update(ctx));
+
+    let mut pass_action = sg::PassAction::default();
+    pass_action.colors[0] = sg::ColorAttachmentAction {
+        action: sg::Action::Clear,
+        value: runner.app.clear_color(),
+    };
+
+    sg::begin_default_pass(&pass_action, sapp::width(), sapp::height());
+    segui.draw();
+    sg::end_pass();
+    sg::commit();
+}
+
+extern "C" fn event(event: *const sapp::Event, userdata: *mut c_void) {
+    let runner = runner(userdata);
+    let event = unsafe { &*event };
+
+    let handled_by_egui = match runner.segui.as_mut() {
+        Some(segui) => segui.event(event),
+        None => false,
+    };
+    runner.app.on_event(event, handled_by_egui);
+}
+
+extern "C" fn cleanup(userdata: *mut c_void) {
+    let mut runner = unsafe { Box::from_raw(userdata as *mut Runner) };
+
+    runner.app.on_exit();
+    if let Some(segui) = runner.segui.take() {
+        segui.shutdown();
+    }
+    sg::shutdown();
+}
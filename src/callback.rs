@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+/*
+    NOTE: Everything is in pixels of the pass that egui is drawn into with the origin in the top
+          left corner, so it can be passed straight to sg::apply_viewport/sg::apply_scissor_rect
+*/
+#[derive(Debug, Copy, Clone)]
+pub struct CallbackInfo {
+    pub viewport: [i32; 4],
+    pub scissor: [i32; 4],
+    pub pixels_per_point: f32,
+    pub pass_width: i32,
+    pub pass_height: i32,
+
+    /*
+        NOTE: The rects in egui points, as given by egui
+    */
+    pub rect: egui::Rect,
+    pub clip_rect: egui::Rect,
+}
+
+/*
+    NOTE: The payload of an egui::PaintCallback that draw() knows how to invoke. The viewport and
+          scissor are already applied when it is called. Anything can be drawn, the egui
+          pipeline and bindings are applied again afterwards
+*/
+pub struct CallbackFn {
+    f: Box<dyn Fn(&CallbackInfo) + Send + Sync>,
+}
+
+impl CallbackFn {
+    pub fn new(f: impl Fn(&CallbackInfo) + Send + Sync + 'static) -> Self {
+        Self { f: Box::new(f) }
+    }
+
+    pub fn call(&self, info: &CallbackInfo) {
+        (self.f)(info)
+    }
+}
+
+pub fn paint_callback(
+    rect: egui::Rect,
+    f: impl Fn(&CallbackInfo) + Send + Sync + 'static,
+) -> egui::PaintCallback {
+    egui::PaintCallback {
+        rect,
+        callback: Arc::new(CallbackFn::new(f)),
+    }
+}
@@ -0,0 +1,160 @@
+use sokol::app as sapp;
+use std::ffi::{c_char, c_void, CStr};
+
+use crate::{Desc, SokolEgui};
+
+/*
+    NOTE: The C api, see include/segui.h. Every function takes the handle returned by
+          segui_setup(), so C code can have as many independent uis as it wants.
+
+          Widgets are called from inside the gui callbacks of the Desc with the egui::Context they
+          are given, or from the contents callback of segui_window() with the egui::Ui.
+
+          The static library already contains the sokol implementation of sokol-rust, so the C
+          app has to link against it instead of compiling its own SOKOL_IMPL
+*/
+
+unsafe fn c_str<'a>(text: *const c_char) -> std::borrow::Cow<'a, str> {
+    if text.is_null() {
+        return std::borrow::Cow::Borrowed("");
+    }
+    CStr::from_ptr(text).to_string_lossy()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn segui_setup(desc: *const Desc) -> *mut SokolEgui {
+    let desc = match desc.as_ref() {
+        Some(desc) => *desc,
+        None => Desc::default(),
+    };
+    Box::into_raw(Box::new(SokolEgui::new(&desc)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn segui_frame(segui: *mut SokolEgui) {
+    if let Some(segui) = segui.as_mut() {
+        segui.frame();
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn segui_draw(segui: *mut SokolEgui) {
+    if let Some(segui) = segui.as_mut() {
+        segui.draw();
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn segui_event(segui: *mut SokolEgui, event: *const sapp::Event) -> bool {
+    match (segui.as_mut(), event.as_ref()) {
+        (Some(segui), Some(event)) => segui.event(event),
+        _ => false,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn segui_shutdown(segui: *mut SokolEgui) {
+    if segui.is_null() {
+        return;
+    }
+    Box::from_raw(segui).shutdown();
+}
+
+/*
+    NOTE: See SokolEgui::set_persistence_path(), the path is ignored without the persistence
+          feature.
+
+          Safety: path has to be NULL or point to a NUL terminated string that stays valid for the
+          duration of the call. It is copied, so it can be freed afterwards
+*/
+#[no_mangle]
+pub unsafe extern "C" fn segui_set_persistence_path(segui: *mut SokolEgui, path: *const c_char) {
+    let Some(segui) = segui.as_mut() else {
+        return;
+    };
+    if path.is_null() {
+        return;
+    }
+    let path = CStr::from_ptr(path).to_string_lossy();
+
+    #[cfg(feature = "persistence")]
+    segui.set_persistence_path(&*path);
+
+    #[cfg(not(feature = "persistence"))]
+    {
+        let _ = segui;
+        println!(
+            "segui segui_set_persistence_path(): '{path}' is ignored without the persistence feature"
+        );
+    }
+}
+
+/*
+    NOTE: Returns false when the window is closed or collapsed, the contents callback is only
+          called when it is visible
+*/
+#[no_mangle]
+pub unsafe extern "C" fn segui_window(
+    ctx: *const egui::Context,
+    title: *const c_char,
+    contents: Option<extern "C" fn(*mut egui::Ui, *mut c_void)>,
+    userdata: *mut c_void,
+) -> bool {
+    let Some(ctx) = ctx.as_ref() else {
+        return false;
+    };
+
+    let response = egui::Window::new(c_str(title)).show(ctx, |ui| {
+        if let Some(contents) = contents {
+            (contents)(ui, userdata);
+        }
+    });
+
+    matches!(response, Some(response) if response.inner.is_some())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn segui_label(ui: *mut egui::Ui, text: *const c_char) {
+    if let Some(ui) = ui.as_mut() {
+        ui.label(c_str(text));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn segui_button(ui: *mut egui::Ui, text: *const c_char) -> bool {
+    match ui.as_mut() {
+        Some(ui) => ui.button(c_str(text)).clicked(),
+        None => false,
+    }
+}
+
+/*
+    NOTE: Returns true when the value was changed this frame
+*/
+#[no_mangle]
+pub unsafe extern "C" fn segui_checkbox(
+    ui: *mut egui::Ui,
+    text: *const c_char,
+    value: *mut bool,
+) -> bool {
+    match (ui.as_mut(), value.as_mut()) {
+        (Some(ui), Some(value)) => ui.checkbox(value, c_str(text)).changed(),
+        _ => false,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn segui_slider_float(
+    ui: *mut egui::Ui,
+    text: *const c_char,
+    value: *mut f32,
+    min: f32,
+    max: f32,
+) -> bool {
+    match (ui.as_mut(), value.as_mut()) {
+        (Some(ui), Some(value)) => ui
+            .add(egui::Slider::new(value, min..=max).text(c_str(text)))
+            .changed(),
+        _ => false,
+    }
+}
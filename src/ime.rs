@@ -0,0 +1,85 @@
+use crate::SokolEgui;
+
+/*
+    NOTE: sokol_app only delivers single characters, so composed text (CJK input methods, dead
+          keys) has to come from the platform layer of the application through this trait
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Composition {
+    Start,
+    Update(String),
+    End(String),
+}
+
+pub trait ImeSource {
+    /*
+        NOTE: Called at the start of every frame, returns what happened since the last call
+    */
+    fn poll(&mut self) -> Vec<Composition>;
+
+    /*
+        NOTE: Whether a text field has keyboard focus, so the input method can be shown or hidden
+    */
+    fn set_enabled(&mut self, _enabled: bool) {}
+
+    /*
+        NOTE: Position of the text cursor in framebuffer pixels, so the candidate window can be
+              placed next to it. None while no text is edited
+    */
+    fn set_cursor_position(&mut self, _position: Option<[f32; 2]>) {}
+}
+
+#[rustfmt::skip]
+pub fn composition_to_egui_event(composition: Composition) -> egui::Event {
+    match composition {
+        Composition::Start        => egui::Event::CompositionStart,
+        Composition::Update(text) => egui::Event::CompositionUpdate(text),
+        Composition::End(text)    => egui::Event::CompositionEnd(text),
+    }
+}
+
+/*
+    NOTE: Control characters come with their own key events. macOS also sends characters from the
+          private use area for function and arrow keys
+*/
+pub fn is_printable_char(char: char) -> bool {
+    let is_in_private_use_area = ('\u{e000}'..='\u{f8ff}').contains(&char)
+        || ('\u{f0000}'..='\u{ffffd}').contains(&char)
+        || ('\u{100000}'..='\u{10fffd}').contains(&char);
+
+    !char.is_control() && !is_in_private_use_area
+}
+
+impl SokolEgui {
+    pub fn set_ime(&mut self, ime: Box<dyn ImeSource>) {
+        self.ime = Some(ime);
+    }
+
+    pub fn clear_ime(&mut self) {
+        self.ime = None;
+    }
+
+    pub(crate) fn poll_ime(&mut self) {
+        let Some(ime) = self.ime.as_mut() else {
+            return;
+        };
+
+        for composition in ime.poll() {
+            self.raw_input
+                .events
+                .push(composition_to_egui_event(composition));
+        }
+    }
+
+    pub(crate) fn update_ime(&mut self, text_cursor_pos: Option<egui::Pos2>) {
+        let Some(ime) = self.ime.as_mut() else {
+            return;
+        };
+
+        let pixels_per_point = self.context.pixels_per_point();
+        ime.set_enabled(self.context.wants_keyboard_input());
+        ime.set_cursor_position(
+            text_cursor_pos.map(|pos| [pos.x * pixels_per_point, pos.y * pixels_per_point]),
+        );
+    }
+}
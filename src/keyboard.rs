@@ -0,0 +1,693 @@
+use sokol::app as sapp;
+use std::collections::HashMap;
+
+/*
+    NOTE: sapp only reports which modifiers are held, not on which side. The modifier keys are
+          tracked here, so that releasing one side doesn't release the modifier while the other
+          side is still held
+*/
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ModifierKeys {
+    pub left_shift: bool,
+    pub right_shift: bool,
+    pub left_control: bool,
+    pub right_control: bool,
+    pub left_alt: bool,
+    pub right_alt: bool,
+    pub left_super: bool,
+    pub right_super: bool,
+}
+
+impl ModifierKeys {
+    /*
+        NOTE: Returns false when the key isn't a modifier key
+    */
+    #[rustfmt::skip]
+    pub fn update(&mut self, key_code: sapp::Keycode, pressed: bool) -> bool {
+        match key_code {
+            sapp::Keycode::LeftShift    => self.left_shift    = pressed,
+            sapp::Keycode::RightShift   => self.right_shift   = pressed,
+            sapp::Keycode::LeftControl  => self.left_control  = pressed,
+            sapp::Keycode::RightControl => self.right_control = pressed,
+            sapp::Keycode::LeftAlt      => self.left_alt      = pressed,
+            sapp::Keycode::RightAlt     => self.right_alt     = pressed,
+            sapp::Keycode::LeftSuper    => self.left_super    = pressed,
+            sapp::Keycode::RightSuper   => self.right_super   = pressed,
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn to_egui_modifiers(&self) -> egui::Modifiers {
+        make_egui_modifiers(
+            self.left_alt || self.right_alt,
+            self.left_control || self.right_control,
+            self.left_shift || self.right_shift,
+            self.left_super || self.right_super,
+        )
+    }
+}
+
+pub fn sapp_modifiers_to_egui_modifiers(modifiers: u32) -> egui::Modifiers {
+    make_egui_modifiers(
+        (modifiers & sapp::MODIFIER_ALT as u32) != 0,
+        (modifiers & sapp::MODIFIER_CTRL as u32) != 0,
+        (modifiers & sapp::MODIFIER_SHIFT as u32) != 0,
+        (modifiers & sapp::MODIFIER_SUPER as u32) != 0,
+    )
+}
+
+/*
+    NOTE: Shortcuts use Cmd on Apple platforms and Ctrl everywhere else
+*/
+fn make_egui_modifiers(alt: bool, ctrl: bool, shift: bool, super_: bool) -> egui::Modifiers {
+    let is_apple = cfg!(any(target_os = "macos", target_os = "ios"));
+
+    egui::Modifiers {
+        alt,
+        ctrl,
+        shift,
+        mac_cmd: is_apple && super_,
+        command: if is_apple { super_ } else { ctrl },
+    }
+}
+
+/*
+    NOTE: Rebinds or suppresses keys on top of sapp_key_to_egui_key(). The callback sees the result
+          of the table and the overrides and has the last word. Lives in SokolEgui::keymap and
+          is only configurable from Rust
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    pub overrides: HashMap<i32, Option<egui::Key>>,
+    pub keymap_cb: Option<fn(sapp::Keycode, Option<egui::Key>) -> Option<egui::Key>>,
+}
+
+impl Keymap {
+    pub fn bind(&mut self, keycode: sapp::Keycode, key: egui::Key) {
+        self.overrides.insert(keycode as i32, Some(key));
+    }
+
+    pub fn suppress(&mut self, keycode: sapp::Keycode) {
+        self.overrides.insert(keycode as i32, None);
+    }
+
+    pub fn reset(&mut self, keycode: sapp::Keycode) {
+        self.overrides.remove(&(keycode as i32));
+    }
+
+    pub fn map(&self, keycode: sapp::Keycode) -> Option<egui::Key> {
+        let key = match self.overrides.get(&(keycode as i32)) {
+            Some(key) => *key,
+            None => sapp_key_to_egui_key(keycode),
+        };
+
+        match self.keymap_cb {
+            Some(cb) => (cb)(keycode, key),
+            None => key,
+        }
+    }
+}
+
+/*
+    NOTE: Key and mouse events carry the held modifiers, the ones of a modifier key event itself
+          come from the tracked modifier keys since platforms disagree on whether the modifier is
+          already set (or still set) in its own event. Returns None for events without modifiers
+*/
+pub fn event_modifiers(
+    modifier_keys: &mut ModifierKeys,
+    event: &sapp::Event,
+) -> Option<egui::Modifiers> {
+    match event._type {
+        sapp::EventType::KeyDown | sapp::EventType::KeyUp => {
+            let pressed = matches!(event._type, sapp::EventType::KeyDown);
+
+            if modifier_keys.update(event.key_code, pressed) {
+                Some(modifier_keys.to_egui_modifiers())
+            } else {
+                Some(sapp_modifiers_to_egui_modifiers(event.modifiers))
+            }
+        }
+
+        sapp::EventType::Char
+        | sapp::EventType::MouseDown
+        | sapp::EventType::MouseUp
+        | sapp::EventType::MouseScroll
+        | sapp::EventType::MouseMove => Some(sapp_modifiers_to_egui_modifiers(event.modifiers)),
+
+        _ => None,
+    }
+}
+
+/*
+    NOTE: Pushes the egui events for a KeyDown or KeyUp event, anything else is ignored
+*/
+pub fn key_event_to_egui_events(
+    keymap: &Keymap,
+    event: &sapp::Event,
+    modifiers: egui::Modifiers,
+    events: &mut Vec<egui::Event>,
+) {
+    let pressed = match event._type {
+        sapp::EventType::KeyDown => true,
+        sapp::EventType::KeyUp => false,
+        _ => return,
+    };
+
+    let Some(key) = keymap.map(event.key_code) else {
+        return;
+    };
+
+    /*
+        NOTE: egui doesn't turn the shortcuts into copy and cut by itself. Paste arrives as
+              sapp::EventType::ClipboardPasted
+    */
+    if pressed && modifiers.command {
+        match key {
+            egui::Key::C => events.push(egui::Event::Copy),
+            egui::Key::X => events.push(egui::Event::Cut),
+            _ => {}
+        }
+    }
+
+    events.push(egui::Event::Key {
+        key,
+        pressed,
+        repeat: event.key_repeat,
+        modifiers,
+    });
+}
+
+/*
+    NOTE: The keycodes sapp_key_to_egui_key() deliberately maps to None
+*/
+pub const IGNORED_KEYCODES: &[sapp::Keycode] = &[
+    sapp::Keycode::Apostrophe,
+    sapp::Keycode::Comma,
+    sapp::Keycode::Period,
+    sapp::Keycode::Slash,
+    sapp::Keycode::Semicolon,
+    sapp::Keycode::LeftBracket,
+    sapp::Keycode::Backslash,
+    sapp::Keycode::RightBracket,
+    sapp::Keycode::GraveAccent,
+    sapp::Keycode::World1,
+    sapp::Keycode::World2,
+    sapp::Keycode::KpDecimal,
+    sapp::Keycode::KpDivide,
+    sapp::Keycode::KpMultiply,
+    sapp::Keycode::LeftShift,
+    sapp::Keycode::LeftControl,
+    sapp::Keycode::LeftAlt,
+    sapp::Keycode::LeftSuper,
+    sapp::Keycode::RightShift,
+    sapp::Keycode::RightControl,
+    sapp::Keycode::RightAlt,
+    sapp::Keycode::RightSuper,
+    sapp::Keycode::F21,
+    sapp::Keycode::F22,
+    sapp::Keycode::F23,
+    sapp::Keycode::F24,
+    sapp::Keycode::F25,
+    sapp::Keycode::CapsLock,
+    sapp::Keycode::ScrollLock,
+    sapp::Keycode::NumLock,
+    sapp::Keycode::PrintScreen,
+    sapp::Keycode::Pause,
+    sapp::Keycode::Menu,
+    sapp::Keycode::Invalid,
+];
+
+/*
+    NOTE: Every keycode is listed, so a new one in sokol_app has to be decided on here
+*/
+#[rustfmt::skip]
+pub fn sapp_key_to_egui_key(keycode: sapp::Keycode) -> Option<egui::Key> {
+    match keycode {
+        sapp::Keycode::Space        => Some(egui::Key::Space),
+        sapp::Keycode::Minus        => Some(egui::Key::Minus),
+        sapp::Keycode::Num0         => Some(egui::Key::Num0),
+        sapp::Keycode::Num1         => Some(egui::Key::Num1),
+        sapp::Keycode::Num2         => Some(egui::Key::Num2),
+        sapp::Keycode::Num3         => Some(egui::Key::Num3),
+        sapp::Keycode::Num4         => Some(egui::Key::Num4),
+        sapp::Keycode::Num5         => Some(egui::Key::Num5),
+        sapp::Keycode::Num6         => Some(egui::Key::Num6),
+        sapp::Keycode::Num7         => Some(egui::Key::Num7),
+        sapp::Keycode::Num8         => Some(egui::Key::Num8),
+        sapp::Keycode::Num9         => Some(egui::Key::Num9),
+        sapp::Keycode::Equal        => Some(egui::Key::PlusEquals),
+        sapp::Keycode::A            => Some(egui::Key::A),
+        sapp::Keycode::B            => Some(egui::Key::B),
+        sapp::Keycode::C            => Some(egui::Key::C),
+        sapp::Keycode::D            => Some(egui::Key::D),
+        sapp::Keycode::E            => Some(egui::Key::E),
+        sapp::Keycode::F            => Some(egui::Key::F),
+        sapp::Keycode::G            => Some(egui::Key::G),
+        sapp::Keycode::H            => Some(egui::Key::H),
+        sapp::Keycode::I            => Some(egui::Key::I),
+        sapp::Keycode::J            => Some(egui::Key::J),
+        sapp::Keycode::K            => Some(egui::Key::K),
+        sapp::Keycode::L            => Some(egui::Key::L),
+        sapp::Keycode::M            => Some(egui::Key::M),
+        sapp::Keycode::N            => Some(egui::Key::N),
+        sapp::Keycode::O            => Some(egui::Key::O),
+        sapp::Keycode::P            => Some(egui::Key::P),
+        sapp::Keycode::Q            => Some(egui::Key::Q),
+        sapp::Keycode::R            => Some(egui::Key::R),
+        sapp::Keycode::S            => Some(egui::Key::S),
+        sapp::Keycode::T            => Some(egui::Key::T),
+        sapp::Keycode::U            => Some(egui::Key::U),
+        sapp::Keycode::V            => Some(egui::Key::V),
+        sapp::Keycode::W            => Some(egui::Key::W),
+        sapp::Keycode::X            => Some(egui::Key::X),
+        sapp::Keycode::Y            => Some(egui::Key::Y),
+        sapp::Keycode::Z            => Some(egui::Key::Z),
+        sapp::Keycode::Escape       => Some(egui::Key::Escape),
+        sapp::Keycode::Enter        => Some(egui::Key::Enter),
+        sapp::Keycode::Tab          => Some(egui::Key::Tab),
+        sapp::Keycode::Backspace    => Some(egui::Key::Backspace),
+        sapp::Keycode::Insert       => Some(egui::Key::Insert),
+        sapp::Keycode::Delete       => Some(egui::Key::Delete),
+        sapp::Keycode::Right        => Some(egui::Key::ArrowRight),
+        sapp::Keycode::Left         => Some(egui::Key::ArrowLeft),
+        sapp::Keycode::Down         => Some(egui::Key::ArrowDown),
+        sapp::Keycode::Up           => Some(egui::Key::ArrowUp),
+        sapp::Keycode::PageUp       => Some(egui::Key::PageUp),
+        sapp::Keycode::PageDown     => Some(egui::Key::PageDown),
+        sapp::Keycode::Home         => Some(egui::Key::Home),
+        sapp::Keycode::End          => Some(egui::Key::End),
+        sapp::Keycode::F1           => Some(egui::Key::F1),
+        sapp::Keycode::F2           => Some(egui::Key::F2),
+        sapp::Keycode::F3           => Some(egui::Key::F3),
+        sapp::Keycode::F4           => Some(egui::Key::F4),
+        sapp::Keycode::F5           => Some(egui::Key::F5),
+        sapp::Keycode::F6           => Some(egui::Key::F6),
+        sapp::Keycode::F7           => Some(egui::Key::F7),
+        sapp::Keycode::F8           => Some(egui::Key::F8),
+        sapp::Keycode::F9           => Some(egui::Key::F9),
+        sapp::Keycode::F10          => Some(egui::Key::F10),
+        sapp::Keycode::F11          => Some(egui::Key::F11),
+        sapp::Keycode::F12          => Some(egui::Key::F12),
+        sapp::Keycode::F13          => Some(egui::Key::F13),
+        sapp::Keycode::F14          => Some(egui::Key::F14),
+        sapp::Keycode::F15          => Some(egui::Key::F15),
+        sapp::Keycode::F16          => Some(egui::Key::F16),
+        sapp::Keycode::F17          => Some(egui::Key::F17),
+        sapp::Keycode::F18          => Some(egui::Key::F18),
+        sapp::Keycode::F19          => Some(egui::Key::F19),
+        sapp::Keycode::F20          => Some(egui::Key::F20),
+
+        /*
+            NOTE: egui has no keys of its own for the keypad
+        */
+        sapp::Keycode::Kp0          => Some(egui::Key::Num0),
+        sapp::Keycode::Kp1          => Some(egui::Key::Num1),
+        sapp::Keycode::Kp2          => Some(egui::Key::Num2),
+        sapp::Keycode::Kp3          => Some(egui::Key::Num3),
+        sapp::Keycode::Kp4          => Some(egui::Key::Num4),
+        sapp::Keycode::Kp5          => Some(egui::Key::Num5),
+        sapp::Keycode::Kp6          => Some(egui::Key::Num6),
+        sapp::Keycode::Kp7          => Some(egui::Key::Num7),
+        sapp::Keycode::Kp8          => Some(egui::Key::Num8),
+        sapp::Keycode::Kp9          => Some(egui::Key::Num9),
+        sapp::Keycode::KpSubtract   => Some(egui::Key::Minus),
+        sapp::Keycode::KpAdd        => Some(egui::Key::PlusEquals),
+        sapp::Keycode::KpEnter      => Some(egui::Key::Enter),
+        sapp::Keycode::KpEqual      => Some(egui::Key::PlusEquals),
+
+        /*
+            NOTE: egui has no key for these, text still arrives through Char events
+        */
+        sapp::Keycode::Apostrophe   => None,
+        sapp::Keycode::Comma        => None,
+        sapp::Keycode::Period       => None,
+        sapp::Keycode::Slash        => None,
+        sapp::Keycode::Semicolon    => None,
+        sapp::Keycode::LeftBracket  => None,
+        sapp::Keycode::Backslash    => None,
+        sapp::Keycode::RightBracket => None,
+        sapp::Keycode::GraveAccent  => None,
+        sapp::Keycode::World1       => None,
+        sapp::Keycode::World2       => None,
+        sapp::Keycode::KpDecimal    => None,
+        sapp::Keycode::KpDivide     => None,
+        sapp::Keycode::KpMultiply   => None,
+
+        /*
+            NOTE: Modifier keys only show up as egui::Modifiers
+        */
+        sapp::Keycode::LeftShift    => None,
+        sapp::Keycode::LeftControl  => None,
+        sapp::Keycode::LeftAlt      => None,
+        sapp::Keycode::LeftSuper    => None,
+        sapp::Keycode::RightShift   => None,
+        sapp::Keycode::RightControl => None,
+        sapp::Keycode::RightAlt     => None,
+        sapp::Keycode::RightSuper   => None,
+
+        sapp::Keycode::F21          => None,
+        sapp::Keycode::F22          => None,
+        sapp::Keycode::F23          => None,
+        sapp::Keycode::F24          => None,
+        sapp::Keycode::F25          => None,
+        sapp::Keycode::CapsLock     => None,
+        sapp::Keycode::ScrollLock   => None,
+        sapp::Keycode::NumLock      => None,
+        sapp::Keycode::PrintScreen  => None,
+        sapp::Keycode::Pause        => None,
+        sapp::Keycode::Menu         => None,
+        sapp::Keycode::Invalid      => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_event(_type: sapp::EventType, key_code: sapp::Keycode, modifiers: u32) -> sapp::Event {
+        sapp::Event {
+            _type,
+            key_code,
+            modifiers,
+            ..Default::default()
+        }
+    }
+
+    fn is_apple() -> bool {
+        cfg!(any(target_os = "macos", target_os = "ios"))
+    }
+
+    #[test]
+    fn update_tracks_modifier_keys_only() {
+        let mut keys = ModifierKeys::default();
+
+        assert!(keys.update(sapp::Keycode::LeftShift, true));
+        assert!(keys.update(sapp::Keycode::RightSuper, true));
+        assert!(!keys.update(sapp::Keycode::A, true));
+        assert!(!keys.update(sapp::Keycode::CapsLock, true));
+
+        assert_eq!(
+            keys,
+            ModifierKeys {
+                left_shift: true,
+                right_super: true,
+                ..Default::default()
+            }
+        );
+
+        assert!(keys.update(sapp::Keycode::LeftShift, false));
+        assert!(!keys.left_shift);
+        assert!(keys.right_super);
+    }
+
+    #[test]
+    fn sapp_modifiers_are_tested_per_bit() {
+        let none = sapp_modifiers_to_egui_modifiers(0);
+        assert_eq!(none, egui::Modifiers::default());
+
+        let shift = sapp_modifiers_to_egui_modifiers(sapp::MODIFIER_SHIFT as u32);
+        assert!(shift.shift);
+        assert!(!shift.alt && !shift.ctrl && !shift.command && !shift.mac_cmd);
+
+        /*
+            NOTE: The mouse button bits share the field and must not turn into modifiers
+        */
+        let buttons = sapp_modifiers_to_egui_modifiers(
+            (sapp::MODIFIER_LMB | sapp::MODIFIER_RMB | sapp::MODIFIER_MMB) as u32,
+        );
+        assert_eq!(buttons, egui::Modifiers::default());
+
+        let all = sapp_modifiers_to_egui_modifiers(
+            (sapp::MODIFIER_ALT
+                | sapp::MODIFIER_CTRL
+                | sapp::MODIFIER_SHIFT
+                | sapp::MODIFIER_SUPER
+                | sapp::MODIFIER_LMB) as u32,
+        );
+        assert!(all.alt && all.ctrl && all.shift && all.command);
+        assert_eq!(all.mac_cmd, is_apple());
+    }
+
+    #[test]
+    fn command_follows_the_platform() {
+        let ctrl = sapp_modifiers_to_egui_modifiers(sapp::MODIFIER_CTRL as u32);
+        assert!(ctrl.ctrl);
+        assert!(!ctrl.mac_cmd);
+        assert_eq!(ctrl.command, !is_apple());
+
+        let super_ = sapp_modifiers_to_egui_modifiers(sapp::MODIFIER_SUPER as u32);
+        assert!(!super_.ctrl);
+        assert_eq!(super_.mac_cmd, is_apple());
+        assert_eq!(super_.command, is_apple());
+    }
+
+    #[test]
+    fn releasing_one_side_keeps_the_other_held() {
+        use sapp::EventType::{KeyDown, KeyUp};
+        use sapp::Keycode::{LeftControl, LeftShift, RightControl, RightShift};
+
+        let mut keys = ModifierKeys::default();
+        let mut modifiers =
+            |_type, key_code| event_modifiers(&mut keys, &key_event(_type, key_code, 0)).unwrap();
+
+        assert!(modifiers(KeyDown, LeftShift).shift);
+        assert!(modifiers(KeyDown, RightShift).shift);
+        assert!(modifiers(KeyUp, LeftShift).shift);
+        assert!(!modifiers(KeyUp, RightShift).shift);
+
+        assert!(modifiers(KeyDown, RightControl).ctrl);
+        assert!(modifiers(KeyDown, LeftControl).ctrl);
+        assert!(modifiers(KeyUp, RightControl).ctrl);
+        assert!(!modifiers(KeyUp, LeftControl).ctrl);
+    }
+
+    #[test]
+    fn modifier_key_events_ignore_the_reported_modifiers() {
+        let mut keys = ModifierKeys::default();
+
+        /*
+            NOTE: Some platforms still report the modifier in its own release event
+        */
+        let release = key_event(
+            sapp::EventType::KeyUp,
+            sapp::Keycode::LeftAlt,
+            sapp::MODIFIER_ALT as u32,
+        );
+        assert!(!event_modifiers(&mut keys, &release).unwrap().alt);
+
+        let other_key = key_event(
+            sapp::EventType::KeyDown,
+            sapp::Keycode::A,
+            sapp::MODIFIER_ALT as u32,
+        );
+        assert!(event_modifiers(&mut keys, &other_key).unwrap().alt);
+
+        let resized = key_event(sapp::EventType::Resized, sapp::Keycode::Invalid, 0);
+        assert_eq!(event_modifiers(&mut keys, &resized), None);
+    }
+
+    #[test]
+    fn key_events_report_pressed_and_repeat() {
+        let keymap = Keymap::default();
+        let mut events = Vec::new();
+
+        let mut down = key_event(sapp::EventType::KeyDown, sapp::Keycode::A, 0);
+        key_event_to_egui_events(&keymap, &down, Default::default(), &mut events);
+
+        down.key_repeat = true;
+        key_event_to_egui_events(&keymap, &down, Default::default(), &mut events);
+
+        let up = key_event(sapp::EventType::KeyUp, sapp::Keycode::A, 0);
+        key_event_to_egui_events(&keymap, &up, Default::default(), &mut events);
+
+        let char = sapp::Event {
+            _type: sapp::EventType::Char,
+            char_code: 'a' as u32,
+            ..Default::default()
+        };
+        key_event_to_egui_events(&keymap, &char, Default::default(), &mut events);
+
+        let keys: Vec<_> = events
+            .iter()
+            .map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed,
+                    repeat,
+                    ..
+                } => (*key, *pressed, *repeat),
+                other => panic!("unexpected event {other:?}"),
+            })
+            .collect();
+
+        assert_eq!(
+            keys,
+            [
+                (egui::Key::A, true, false),
+                (egui::Key::A, true, true),
+                (egui::Key::A, false, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn command_c_and_x_copy_and_cut_on_press() {
+        let keymap = Keymap::default();
+        let command = egui::Modifiers::COMMAND;
+        let mut events = Vec::new();
+
+        let copy = key_event(sapp::EventType::KeyDown, sapp::Keycode::C, 0);
+        key_event_to_egui_events(&keymap, &copy, command, &mut events);
+        assert!(matches!(
+            events[..],
+            [egui::Event::Copy, egui::Event::Key { .. }]
+        ));
+
+        events.clear();
+        let cut = key_event(sapp::EventType::KeyDown, sapp::Keycode::X, 0);
+        key_event_to_egui_events(&keymap, &cut, command, &mut events);
+        assert!(matches!(
+            events[..],
+            [egui::Event::Cut, egui::Event::Key { .. }]
+        ));
+
+        events.clear();
+        let release = key_event(sapp::EventType::KeyUp, sapp::Keycode::C, 0);
+        key_event_to_egui_events(&keymap, &release, command, &mut events);
+        assert!(matches!(
+            events[..],
+            [egui::Event::Key { pressed: false, .. }]
+        ));
+
+        events.clear();
+        key_event_to_egui_events(&keymap, &copy, Default::default(), &mut events);
+        assert!(matches!(
+            events[..],
+            [egui::Event::Key { pressed: true, .. }]
+        ));
+    }
+
+    #[rustfmt::skip]
+    const ALL_KEYCODES: &[sapp::Keycode] = &[
+        sapp::Keycode::Space, sapp::Keycode::Minus, sapp::Keycode::Num0, sapp::Keycode::Num1,
+        sapp::Keycode::Num2, sapp::Keycode::Num3, sapp::Keycode::Num4, sapp::Keycode::Num5,
+        sapp::Keycode::Num6, sapp::Keycode::Num7, sapp::Keycode::Num8, sapp::Keycode::Num9,
+        sapp::Keycode::Equal, sapp::Keycode::A, sapp::Keycode::B, sapp::Keycode::C,
+        sapp::Keycode::D, sapp::Keycode::E, sapp::Keycode::F, sapp::Keycode::G,
+        sapp::Keycode::H, sapp::Keycode::I, sapp::Keycode::J, sapp::Keycode::K,
+        sapp::Keycode::L, sapp::Keycode::M, sapp::Keycode::N, sapp::Keycode::O,
+        sapp::Keycode::P, sapp::Keycode::Q, sapp::Keycode::R, sapp::Keycode::S,
+        sapp::Keycode::T, sapp::Keycode::U, sapp::Keycode::V, sapp::Keycode::W,
+        sapp::Keycode::X, sapp::Keycode::Y, sapp::Keycode::Z, sapp::Keycode::Escape,
+        sapp::Keycode::Enter, sapp::Keycode::Tab, sapp::Keycode::Backspace, sapp::Keycode::Insert,
+        sapp::Keycode::Delete, sapp::Keycode::Right, sapp::Keycode::Left, sapp::Keycode::Down,
+        sapp::Keycode::Up, sapp::Keycode::PageUp, sapp::Keycode::PageDown, sapp::Keycode::Home,
+        sapp::Keycode::End, sapp::Keycode::F1, sapp::Keycode::F2, sapp::Keycode::F3,
+        sapp::Keycode::F4, sapp::Keycode::F5, sapp::Keycode::F6, sapp::Keycode::F7,
+        sapp::Keycode::F8, sapp::Keycode::F9, sapp::Keycode::F10, sapp::Keycode::F11,
+        sapp::Keycode::F12, sapp::Keycode::F13, sapp::Keycode::F14, sapp::Keycode::F15,
+        sapp::Keycode::F16, sapp::Keycode::F17, sapp::Keycode::F18, sapp::Keycode::F19,
+        sapp::Keycode::F20, sapp::Keycode::Kp0, sapp::Keycode::Kp1, sapp::Keycode::Kp2,
+        sapp::Keycode::Kp3, sapp::Keycode::Kp4, sapp::Keycode::Kp5, sapp::Keycode::Kp6,
+        sapp::Keycode::Kp7, sapp::Keycode::Kp8, sapp::Keycode::Kp9, sapp::Keycode::KpSubtract,
+        sapp::Keycode::KpAdd, sapp::Keycode::KpEnter, sapp::Keycode::KpEqual, sapp::Keycode::Apostrophe,
+        sapp::Keycode::Comma, sapp::Keycode::Period, sapp::Keycode::Slash, sapp::Keycode::Semicolon,
+        sapp::Keycode::LeftBracket, sapp::Keycode::Backslash, sapp::Keycode::RightBracket, sapp::Keycode::GraveAccent,
+        sapp::Keycode::World1, sapp::Keycode::World2, sapp::Keycode::KpDecimal, sapp::Keycode::KpDivide,
+        sapp::Keycode::KpMultiply, sapp::Keycode::LeftShift, sapp::Keycode::LeftControl, sapp::Keycode::LeftAlt,
+        sapp::Keycode::LeftSuper, sapp::Keycode::RightShift, sapp::Keycode::RightControl, sapp::Keycode::RightAlt,
+        sapp::Keycode::RightSuper, sapp::Keycode::F21, sapp::Keycode::F22, sapp::Keycode::F23,
+        sapp::Keycode::F24, sapp::Keycode::F25, sapp::Keycode::CapsLock, sapp::Keycode::ScrollLock,
+        sapp::Keycode::NumLock, sapp::Keycode::PrintScreen, sapp::Keycode::Pause, sapp::Keycode::Menu,
+        sapp::Keycode::Invalid,
+    ];
+
+    #[test]
+    fn every_keycode_is_mapped_or_ignored() {
+        let mut seen = std::collections::HashSet::new();
+        for keycode in ALL_KEYCODES {
+            assert!(seen.insert(*keycode as i32), "{keycode:?} is listed twice");
+        }
+
+        for keycode in IGNORED_KEYCODES {
+            assert!(
+                seen.contains(&(*keycode as i32)),
+                "{keycode:?} is ignored but missing from ALL_KEYCODES"
+            );
+        }
+
+        for keycode in ALL_KEYCODES {
+            let ignored = IGNORED_KEYCODES
+                .iter()
+                .any(|ignored| *ignored as i32 == *keycode as i32);
+            let mapped = sapp_key_to_egui_key(*keycode).is_some();
+
+            assert!(
+                mapped != ignored,
+                "{keycode:?} must be either mapped or in IGNORED_KEYCODES"
+            );
+        }
+    }
+
+    #[test]
+    fn keymap_overrides_the_table() {
+        let mut keymap = Keymap::default();
+        assert_eq!(keymap.map(sapp::Keycode::A), Some(egui::Key::A));
+        assert_eq!(keymap.map(sapp::Keycode::Comma), None);
+
+        keymap.bind(sapp::Keycode::A, egui::Key::B);
+        keymap.bind(sapp::Keycode::Comma, egui::Key::Minus);
+        assert_eq!(keymap.map(sapp::Keycode::A), Some(egui::Key::B));
+        assert_eq!(keymap.map(sapp::Keycode::Comma), Some(egui::Key::Minus));
+
+        keymap.suppress(sapp::Keycode::A);
+        assert_eq!(keymap.map(sapp::Keycode::A), None);
+
+        keymap.reset(sapp::Keycode::A);
+        keymap.reset(sapp::Keycode::Comma);
+        assert_eq!(keymap.map(sapp::Keycode::A), Some(egui::Key::A));
+        assert_eq!(keymap.map(sapp::Keycode::Comma), None);
+
+        /*
+            NOTE: Resetting a key without an override is a no-op
+        */
+        keymap.reset(sapp::Keycode::Z);
+        assert_eq!(keymap.map(sapp::Keycode::Z), Some(egui::Key::Z));
+    }
+
+    #[test]
+    fn keymap_cb_has_the_last_word() {
+        fn swap_a_and_b(keycode: sapp::Keycode, key: Option<egui::Key>) -> Option<egui::Key> {
+            match keycode {
+                sapp::Keycode::A => Some(egui::Key::B),
+                sapp::Keycode::B => Some(egui::Key::A),
+                sapp::Keycode::Comma => None,
+                _ => key,
+            }
+        }
+
+        fn echo(_keycode: sapp::Keycode, key: Option<egui::Key>) -> Option<egui::Key> {
+            key
+        }
+
+        let mut keymap = Keymap {
+            keymap_cb: Some(swap_a_and_b),
+            ..Default::default()
+        };
+
+        keymap.bind(sapp::Keycode::A, egui::Key::C);
+        keymap.bind(sapp::Keycode::Comma, egui::Key::Minus);
+        assert_eq!(keymap.map(sapp::Keycode::A), Some(egui::Key::B));
+        assert_eq!(keymap.map(sapp::Keycode::B), Some(egui::Key::A));
+        assert_eq!(keymap.map(sapp::Keycode::Comma), None);
+
+        /*
+            NOTE: The callback sees the result of the overrides
+        */
+        keymap.keymap_cb = Some(echo);
+        assert_eq!(keymap.map(sapp::Keycode::A), Some(egui::Key::C));
+        keymap.suppress(sapp::Keycode::D);
+        assert_eq!(keymap.map(sapp::Keycode::D), None);
+    }
+}
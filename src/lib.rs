@@ -1,171 +1,1638 @@
 use sokol::app as sapp;
 use sokol::gfx as sg;
+use sokol::time as stm;
+
+pub mod app;
+pub mod callback;
+mod capi;
+pub mod ime;
+pub mod keyboard;
+pub mod overlay;
+pub mod panics;
+#[cfg(feature = "persistence")]
+pub mod persistence;
+#[cfg(feature = "sgl")]
+pub mod sgl;
+mod shader;
+pub mod software;
+pub mod textures;
+
+pub use app::{run, App, WindowOptions};
+pub use callback::{paint_callback, CallbackFn, CallbackInfo};
+pub use keyboard::{sapp_key_to_egui_key, Keymap};
+pub use overlay::{draw_overlay, overlay, overlay_with_desc};
+pub use software::SoftwareRenderer;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(C)]
+pub enum BlendMode {
+    /*
+        NOTE: egui outputs premultiplied colors and the pass ends up premultiplied too. A pass that
+              is later composited with straight alpha has to be converted by the app
+    */
+    Premultiplied,
+}
+
+/*
+    NOTE: Has to match the attachments of the pass the pipeline is used in. The defaults
+          (sg::PixelFormat::Default and a sample count of 0) use the ones of the default pass
+*/
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct PipelineDesc {
+    pub color_format: sg::PixelFormat,
+    pub depth_format: sg::PixelFormat,
+    pub sample_count: i32,
+    pub blend_mode: BlendMode,
+}
+
+impl PipelineDesc {
+    pub const fn new() -> Self {
+        Self {
+            color_format: sg::PixelFormat::Default,
+            depth_format: sg::PixelFormat::Default,
+            sample_count: 0,
+            blend_mode: BlendMode::Premultiplied,
+        }
+    }
+}
+
+impl Default for PipelineDesc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(C)]
+pub enum Renderer {
+    Gfx,
+
+    /*
+        NOTE: Draws through sokol_gl, without any shader or buffers of our own.
+              Paint callbacks are not supported
+    */
+    #[cfg(feature = "sgl")]
+    Sgl,
+}
+
+/*
+    NOTE: Decides which events event() reports as handled, i.e. which ones the application should
+          not react to itself. egui sees every event regardless, and releases (KeyUp, MouseUp,
+          TouchesEnded, TouchesCancelled) are never reported as handled
+*/
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(C)]
+pub enum InputCapture {
+    /*
+        NOTE: Pointer events while egui uses the pointer or hovers something interactive,
+              keyboard events while a widget has keyboard focus. First so that a zeroed Desc
+              (e.g. from C) gets the same default as Desc::new()
+    */
+    WhenEguiWantsInput,
+
+    AlwaysForward,
+
+    /*
+        NOTE: Pointer events only while the pointer is over an egui area (or drags something
+              that started in one), keyboard events while a widget has keyboard focus
+    */
+    OverEguiArea,
+}
 
 #[derive(Debug, Copy, Clone)]
 #[repr(C)]
 pub struct Desc {
-    pub gui_cb: Option<extern "C" fn(*const egui::Context)>,
-    pub gui_userdata_cb: Option<extern "C" fn(*const egui::Context, *mut core::ffi::c_void)>,
+    pub gui_cb: Option<extern "C-unwind" fn(*const egui::Context)>,
+    pub gui_userdata_cb: Option<extern "C-unwind" fn(*const egui::Context, *mut core::ffi::c_void)>,
     pub userdata: *mut core::ffi::c_void,
+    pub pipeline: PipelineDesc,
+    pub renderer: Renderer,
+
+    /*
+        NOTE: Initial number of vertices and indices the buffers have room for, they grow when a
+              frame needs more. 0 picks DEFAULT_VERTEX_CAPACITY/DEFAULT_INDEX_CAPACITY
+    */
+    pub initial_vertex_capacity: usize,
+    pub initial_index_capacity: usize,
+
+    /*
+        NOTE: Seconds between saves of the egui memory to the storage given to
+              set_persistence_path() or set_storage(), 0 picks DEFAULT_AUTOSAVE_INTERVAL. Needs
+              the persistence feature
+    */
+    pub autosave_interval: f64,
+
+    /*
+        NOTE: Panics in the gui callbacks are caught and shown in a window instead. After this many
+              frames in a row that panicked, the panic is raised again (which aborts when frame()
+              is called from a sokol callback). 0 never raises it
+    */
+    pub max_consecutive_panics: u32,
+
+    /*
+        NOTE: Seconds for egui::RawInput::time, to drive egui with a clock of its own (e.g. in tests
+              and replays). Without one, the time since begin() from sokol_time is used
+    */
+    pub time_cb: Option<extern "C" fn() -> f64>,
+    pub time_userdata_cb: Option<extern "C" fn(*mut core::ffi::c_void) -> f64>,
+
+    /*
+        NOTE: Dropped files up to this size are read right away into egui::DroppedFile::bytes,
+              0 never reads them. Dropping files has to be enabled in the sapp::Desc
+    */
+    pub max_dropped_file_read_size: usize,
+
+    /*
+        NOTE: consume_events calls sapp::consume_event() for every handled event, which keeps the
+              browser from acting on it on the web
+    */
+    pub input_capture: InputCapture,
+    pub consume_events: bool,
+}
+
+impl Desc {
+    const fn new() -> Self {
+        Self {
+            gui_cb: None,
+            gui_userdata_cb: None,
+            userdata: core::ptr::null_mut(),
+            pipeline: PipelineDesc::new(),
+            renderer: Renderer::Gfx,
+            initial_vertex_capacity: 0,
+            initial_index_capacity: 0,
+            autosave_interval: 0.0,
+            max_consecutive_panics: 0,
+            time_cb: None,
+            time_userdata_cb: None,
+            max_dropped_file_read_size: 0,
+            input_capture: InputCapture::WhenEguiWantsInput,
+            consume_events: false,
+        }
+    }
 }
 
-impl Desc {
-    const fn new() -> Self {
-        Self {
-            gui_cb: None,
-            gui_userdata_cb: None,
-            userdata: core::ptr::null_mut(),
+impl Default for Desc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct SokolEgui {
+    pub context: egui::Context,
+    pub raw_input: egui::RawInput,
+    pub latest_output: Option<egui::FullOutput>,
+    pub gui_cb: Option<extern "C-unwind" fn(*const egui::Context)>,
+    pub gui_userdata_cb: Option<extern "C-unwind" fn(*const egui::Context, *mut core::ffi::c_void)>,
+    pub userdata: *mut core::ffi::c_void,
+
+    /*
+        NOTE: The Rust alternative to the callbacks in Desc, see set_gui()
+    */
+    pub gui: Option<Box<dyn FnMut(&egui::Context)>>,
+
+    pub renderer: Renderer,
+    #[cfg(feature = "sgl")]
+    pub sgl_renderer: Option<sgl::SglRenderer>,
+
+    pub shader: sg::Shader,
+    pub pipeline: sg::Pipeline,
+    pub bindings: sg::Bindings,
+    pub white_image: sg::Image,
+    pub textures: textures::Textures,
+    pub textures_to_free: Vec<egui::TextureId>,
+    pub pending_textures_delta: egui::TexturesDelta,
+    pub output_prepared: bool,
+    pub clipped_primitives: Vec<egui::ClippedPrimitive>,
+    pub draw_calls: Vec<DrawCall>,
+    pub vertex_capacity: usize,
+    pub index_capacity: usize,
+    pub stats: Stats,
+
+    pub released: bool,
+
+    pub modifier_keys: keyboard::ModifierKeys,
+    pub keymap: Keymap,
+    pub max_dropped_file_read_size: usize,
+    pub ime: Option<Box<dyn ime::ImeSource>>,
+    pub input_capture: InputCapture,
+    pub consume_events: bool,
+
+    pub time_cb: Option<extern "C" fn() -> f64>,
+    pub time_userdata_cb: Option<extern "C" fn(*mut core::ffi::c_void) -> f64>,
+    pub start_time: u64,
+
+    pub last_panic: Option<panics::GuiPanic>,
+    pub consecutive_panics: u32,
+    pub max_consecutive_panics: u32,
+
+    #[cfg(feature = "persistence")]
+    pub storage: Option<Box<dyn persistence::Storage>>,
+    #[cfg(feature = "persistence")]
+    pub last_save: std::time::Instant,
+    #[cfg(feature = "persistence")]
+    pub autosave_interval: f64,
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[repr(C)]
+pub struct Stats {
+    pub num_meshes: usize,
+    pub num_callbacks: usize,
+    pub num_vertices: usize,
+    pub num_indices: usize,
+    pub uploaded_bytes: usize,
+    pub vertex_capacity: usize,
+    pub index_capacity: usize,
+    pub num_buffer_resizes: usize,
+}
+
+#[derive(Clone)]
+pub enum DrawCall {
+    Mesh {
+        clip_rect: egui::Rect,
+        texture_id: egui::TextureId,
+        vertex_buffer_offset: i32,
+        index_buffer_offset: i32,
+        num_elements: usize,
+    },
+    Callback {
+        clip_rect: egui::Rect,
+        callback: egui::PaintCallback,
+    },
+}
+
+pub const DEFAULT_VERTEX_CAPACITY: usize = 1 << 16;
+pub const DEFAULT_INDEX_CAPACITY: usize = DEFAULT_VERTEX_CAPACITY * 3;
+
+impl SokolEgui {
+    pub fn new(desc: &Desc) -> Self {
+        let mut shader = sg::Shader::new();
+        let mut pipeline = sg::Pipeline::new();
+        let mut vertex_buffer = sg::Buffer::new();
+        let mut index_buffer = sg::Buffer::new();
+
+        let vertex_capacity = match desc.initial_vertex_capacity {
+            0 => DEFAULT_VERTEX_CAPACITY,
+            capacity => capacity,
+        };
+        let index_capacity = match desc.initial_index_capacity {
+            0 => DEFAULT_INDEX_CAPACITY,
+            capacity => capacity,
+        };
+
+        #[cfg(feature = "sgl")]
+        let sgl_renderer = match desc.renderer {
+            Renderer::Sgl => Some(sgl::SglRenderer::new(&desc.pipeline, index_capacity)),
+            Renderer::Gfx => None,
+        };
+
+        if desc.renderer == Renderer::Gfx {
+            shader = sg::make_shader(&shader::egui_shader_desc(sg::query_backend()));
+            pipeline = make_egui_pipeline(shader, &desc.pipeline);
+            vertex_buffer = make_vertex_buffer(vertex_capacity);
+            index_buffer = make_index_buffer(index_capacity);
+        }
+
+        /*
+            NOTE: Bound for meshes whose texture is unknown, so that they still show up
+        */
+        let white_pixel: [u8; 4] = [0xFF; 4];
+        let mut white_image_desc = sg::ImageDesc {
+            width: 1,
+            height: 1,
+            pixel_format: sg::PixelFormat::Rgba8,
+            label: b"segui-white-image\0".as_ptr() as _,
+            ..Default::default()
+        };
+        white_image_desc.data.subimage[0][0] = sg::slice_as_range(&white_pixel);
+        let white_image = sg::make_image(&white_image_desc);
+
+        let mut bindings = sg::Bindings::new();
+        bindings.vertex_buffers[0] = vertex_buffer;
+        bindings.index_buffer = index_buffer;
+        bindings.fs_images[shader::SLOT_TEX] = white_image;
+
+        panics::install_hook();
+        check_clipboard_desc();
+
+        /*
+            NOTE: stm_setup() restarts the clock of sokol_time, so it is only done for the first
+                  instance and not again for every other one
+        */
+        static SETUP_TIME: std::sync::Once = std::sync::Once::new();
+        SETUP_TIME.call_once(stm::setup);
+
+        #[allow(unused_mut)]
+        let mut segui = Self {
+            context: egui::Context::default(),
+            raw_input: egui::RawInput::default(),
+            latest_output: None,
+
+            gui_cb: desc.gui_cb,
+            gui_userdata_cb: desc.gui_userdata_cb,
+            userdata: desc.userdata,
+            gui: None,
+
+            renderer: desc.renderer,
+            #[cfg(feature = "sgl")]
+            sgl_renderer,
+
+            shader,
+            pipeline,
+            bindings,
+            white_image,
+            textures: textures::Textures::new(),
+            textures_to_free: Vec::new(),
+            pending_textures_delta: Default::default(),
+            output_prepared: false,
+            clipped_primitives: Vec::new(),
+            draw_calls: Vec::new(),
+            vertex_capacity,
+            index_capacity,
+            stats: Stats::default(),
+
+            released: false,
+
+            modifier_keys: keyboard::ModifierKeys::default(),
+            keymap: Keymap::default(),
+            max_dropped_file_read_size: desc.max_dropped_file_read_size,
+            ime: None,
+            input_capture: desc.input_capture,
+            consume_events: desc.consume_events,
+
+            time_cb: desc.time_cb,
+            time_userdata_cb: desc.time_userdata_cb,
+            start_time: stm::now(),
+
+            last_panic: None,
+            consecutive_panics: 0,
+            max_consecutive_panics: desc.max_consecutive_panics,
+
+            #[cfg(feature = "persistence")]
+            storage: None,
+            #[cfg(feature = "persistence")]
+            last_save: std::time::Instant::now(),
+            #[cfg(feature = "persistence")]
+            autosave_interval: match desc.autosave_interval {
+                interval if interval > 0.0 => interval,
+                _ => persistence::DEFAULT_AUTOSAVE_INTERVAL,
+            },
+        };
+
+        #[cfg(feature = "persistence")]
+        segui.load_memory();
+
+        segui
+    }
+
+    /*
+        NOTE: Called every frame() after the callbacks from the Desc. Owns whatever it captures, so
+              no userdata pointer has to be passed around
+    */
+    pub fn set_gui(&mut self, gui: impl FnMut(&egui::Context) + 'static) {
+        self.gui = Some(Box::new(gui));
+    }
+
+    pub fn clear_gui(&mut self) {
+        self.gui = None;
+    }
+
+    pub fn time(&self) -> f64 {
+        if let Some(cb) = self.time_cb {
+            (cb)()
+        } else if let Some(cb) = self.time_userdata_cb {
+            (cb)(self.userdata)
+        } else {
+            stm::sec(stm::since(self.start_time))
+        }
+    }
+
+    pub fn frame(&mut self) {
+        self.frame_with(|_| {});
+    }
+
+    /*
+        NOTE: Runs a frame with the given gui on top of the registered ones and returns its output.
+              The output stays around to be drawn by draw()/draw_to_pass()
+    */
+    pub fn frame_with(&mut self, gui: impl FnOnce(&egui::Context)) -> &egui::FullOutput {
+        self.poll_ime();
+
+        let mut input = self.raw_input.clone();
+        {
+            input.screen_rect.replace(egui::Rect {
+                max: egui::pos2(
+                    sapp::widthf() / sapp::dpi_scale(),
+                    sapp::heightf() / sapp::dpi_scale(),
+                ),
+                min: egui::pos2(0.0, 0.0),
+            });
+            input.pixels_per_point.replace(sapp::dpi_scale());
+            input.predicted_dt = sapp::frame_duration() as _;
+            input.time = Some(self.time());
+        }
+        self.context.begin_frame(input);
+
+        /*
+            NOTE: Panics must not unwind into sokol, so every callback is run on its own and the
+                  frame is finished normally afterwards
+        */
+        let mut caught_panics = Vec::new();
+        let context = &self.context;
+        if let Some(cb) = self.gui_cb {
+            caught_panics.extend(panics::catch(|| (cb)(context)));
+        }
+        if let Some(cb) = self.gui_userdata_cb {
+            let userdata = self.userdata;
+            caught_panics.extend(panics::catch(|| (cb)(context, userdata)));
+        }
+        if let Some(gui) = self.gui.as_mut() {
+            caught_panics.extend(panics::catch(|| (gui)(context)));
+        }
+        caught_panics.extend(panics::catch(|| gui(context)));
+
+        if let Some((panic, payload)) = caught_panics.pop() {
+            self.consecutive_panics += 1;
+            println!(
+                "segui frame(): caught panic in gui callback: {}",
+                panic.message
+            );
+            self.last_panic = Some(panic);
+
+            if self.max_consecutive_panics > 0
+                && self.consecutive_panics >= self.max_consecutive_panics
+            {
+                std::panic::resume_unwind(payload);
+            }
+        } else {
+            self.consecutive_panics = 0;
+        }
+
+        if let Some(panic) = self.last_panic.as_ref() {
+            if panics::show_error_window(&self.context, panic, self.consecutive_panics) {
+                self.last_panic = None;
+            }
+        }
+
+        self.raw_input.events.clear();
+        self.raw_input.events.clear();
+        self.raw_input.dropped_files.clear();
+
+        let mut output = self.context.end_frame();
+
+        sapp::set_mouse_cursor(egui_cursor_to_sapp_cursor(
+            output.platform_output.cursor_icon,
+        ));
+        self.update_ime(output.platform_output.text_cursor_pos);
+
+        // for event in &output.platform_output.events {
+        //     match event {
+        //         egui::output::OutputEvent::Clicked(_) => {}
+        //         egui::output::OutputEvent::DoubleClicked(_) => {}
+        //         egui::output::OutputEvent::TripleClicked(_) => {}
+        //         egui::output::OutputEvent::FocusGained(_) => {}
+        //         egui::output::OutputEvent::TextSelectionChanged(_) => {}
+        //         egui::output::OutputEvent::ValueChanged(_) => {}
+        //     }
+        // }
+
+        if let Some(request) = output.platform_output.open_url.as_ref() {
+            /*
+                TODO: Open the url
+            */
+            println!(
+                "segui frame(): should open the url '{}' in new tab: {}",
+                &request.url, request.new_tab
+            );
+        }
+
+        if !output.platform_output.copied_text.is_empty() {
+            sapp::set_clipboard_string(&output.platform_output.copied_text);
+        }
+
+        #[cfg(feature = "persistence")]
+        self.autosave();
+
+        /*
+            NOTE: The texture changes are queued rather than left in the output, so that they
+                  aren't lost when frame() runs several times before the next draw()
+        */
+        self.pending_textures_delta
+            .append(std::mem::take(&mut output.textures_delta));
+
+        self.output_prepared = false;
+        self.latest_output.insert(output)
+    }
+
+    pub fn draw(&mut self) {
+        if self.latest_output.is_some() {
+            self.prepare_output();
+            let pipeline = self.pipeline;
+            self.render_clipped_primitives(pipeline, sapp::width(), sapp::height());
+
+            if let Some(output) = self.latest_output.as_ref() {
+                if !output.repaint_after.is_zero() {
+                    /*
+                        TODO: Handle egui wait fo revents mode
+                    */
+                }
+            }
+        }
+        /*
+            TODO: else { something is wrong, log? }
+        */
+    }
+
+    /*
+        NOTE: Renders the latest output into an offscreen pass. The whole egui screen is scaled to
+              fit the given size, so it can be any size and doesn't have to match the window.
+
+              The pass attachments have to match the pipeline, see make_pipeline() for passes that
+              don't match the Desc given to begin()
+    */
+    pub fn draw_to_pass(
+        &mut self,
+        pass: sg::Pass,
+        pass_action: &sg::PassAction,
+        width: i32,
+        height: i32,
+    ) {
+        self.draw_to_pass_with_pipeline(self.pipeline, pass, pass_action, width, height);
+    }
+
+    pub fn draw_to_pass_with_pipeline(
+        &mut self,
+        pipeline: sg::Pipeline,
+        pass: sg::Pass,
+        pass_action: &sg::PassAction,
+        width: i32,
+        height: i32,
+    ) {
+        if self.latest_output.is_some() {
+            self.prepare_output();
+
+            sg::begin_pass(pass, pass_action);
+            self.render_clipped_primitives(pipeline, width, height);
+            sg::end_pass();
+        }
+    }
+
+    /*
+        NOTE: Creates an additional pipeline for passes that don't match the one given to begin(),
+              to be used with draw_to_pass_with_pipeline(). It is owned by the caller and has to be
+              destroyed with sg::destroy_pipeline() before shutdown
+    */
+    pub fn make_pipeline(&self, desc: &PipelineDesc) -> sg::Pipeline {
+        make_egui_pipeline(self.shader, desc)
+    }
+
+    /*
+        NOTE: Describes what the last prepared output uploaded, i.e. the frame drawn last
+    */
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn register_user_texture(&mut self, image: sg::Image) -> egui::TextureId {
+        self.textures.register_user(image)
+    }
+
+    pub fn replace_user_texture(&mut self, texture_id: egui::TextureId, image: sg::Image) -> bool {
+        self.textures.replace_user(texture_id, image)
+    }
+
+    pub fn unregister_user_texture(&mut self, texture_id: egui::TextureId) -> Option<sg::Image> {
+        self.textures.unregister_user(texture_id)
+    }
+
+    pub fn event(&mut self, event: &sapp::Event) -> bool {
+        let mut was_handled = false;
+
+        if let Some(modifiers) = keyboard::event_modifiers(&mut self.modifier_keys, event) {
+            self.raw_input.modifiers = modifiers;
+        }
+
+        /*
+            NOTE: Every input event goes to egui (key events so that shortcuts work without a
+                  focused widget, pointer events so that egui knows what is hovered). Whether it
+                  counts as handled is up to the InputCapture policy
+        */
+        let captures_keyboard = self.captures_keyboard();
+        let captures_pointer = self.captures_pointer();
+
+        was_handled |= match event._type {
+            sapp::EventType::KeyDown | sapp::EventType::KeyUp => {
+                keyboard::key_event_to_egui_events(
+                    &self.keymap,
+                    event,
+                    self.raw_input.modifiers,
+                    &mut self.raw_input.events,
+                );
+
+                /*
+                    NOTE: Releases are never captured, so the app sees the release of every key it
+                          saw pressed, even when egui wants the keyboard in between
+                */
+                captures_keyboard && matches!(event._type, sapp::EventType::KeyDown)
+            }
+
+            sapp::EventType::Char => {
+                if let Some(char) =
+                    char::from_u32(event.char_code).filter(|char| ime::is_printable_char(*char))
+                {
+                    self.raw_input
+                        .events
+                        .push(egui::Event::Text(char.to_string()));
+                }
+
+                captures_keyboard
+            }
+
+            _ => false,
+        };
+
+        {
+            /*
+                TODO: Is this valid? Is there an equivalent of TouchDeviceId in sapp?
+            */
+            const FAKE_TOUCH_DEVICE_ID: egui::TouchDeviceId = egui::TouchDeviceId(1337);
+
+            /*
+                NOTE: sapp reports positions in framebuffer pixels, egui wants points
+            */
+            let points_per_pixel = 1.0 / sapp::dpi_scale();
+            let mouse_pos = egui::pos2(
+                event.mouse_x * points_per_pixel,
+                event.mouse_y * points_per_pixel,
+            );
+
+            let is_pointer_event = match event._type {
+                sapp::EventType::MouseDown => {
+                    self.raw_input.events.push(egui::Event::PointerButton {
+                        pos: mouse_pos,
+                        button: sapp_mouse_button_to_egui_pointer_button(event.mouse_button),
+                        pressed: true,
+                        modifiers: self.raw_input.modifiers,
+                    });
+                    true
+                }
+                sapp::EventType::MouseUp => {
+                    self.raw_input.events.push(egui::Event::PointerButton {
+                        pos: mouse_pos,
+                        button: sapp_mouse_button_to_egui_pointer_button(event.mouse_button),
+                        pressed: false,
+                        modifiers: self.raw_input.modifiers,
+                    });
+                    true
+                }
+                sapp::EventType::MouseScroll => {
+                    self.raw_input.events.push(egui::Event::Scroll(egui::vec2(
+                        event.scroll_x,
+                        event.scroll_y,
+                    )));
+                    true
+                }
+
+                sapp::EventType::MouseMove => {
+                    self.raw_input
+                        .events
+                        .push(egui::Event::PointerMoved(mouse_pos));
+                    true
+                }
+
+                sapp::EventType::MouseEnter => {
+                    self.raw_input
+                        .events
+                        .push(egui::Event::PointerMoved(mouse_pos));
+                    true
+                }
+                sapp::EventType::MouseLeave => {
+                    self.raw_input.events.push(egui::Event::PointerGone);
+                    true
+                }
+
+                sapp::EventType::TouchesBegan => {
+                    let mut handled = false;
+                    for touch in &event.touches[..event.num_touches as usize] {
+                        if touch.changed {
+                            self.raw_input.events.push(egui::Event::Touch {
+                                device_id: FAKE_TOUCH_DEVICE_ID,
+                                id: egui::TouchId(touch.identifier as _),
+                                phase: egui::TouchPhase::Start,
+                                pos: egui::pos2(
+                                    touch.pos_x * points_per_pixel,
+                                    touch.pos_y * points_per_pixel,
+                                ),
+                                force: 0.0,
+                            });
+                            handled = true;
+                        }
+                    }
+
+                    handled
+                }
+                sapp::EventType::TouchesMoved => {
+                    let mut handled = false;
+                    for touch in &event.touches[..event.num_touches as usize] {
+                        if touch.changed {
+                            self.raw_input.events.push(egui::Event::Touch {
+                                device_id: FAKE_TOUCH_DEVICE_ID,
+                                id: egui::TouchId(touch.identifier as _),
+                                phase: egui::TouchPhase::Move,
+                                pos: egui::pos2(
+                                    touch.pos_x * points_per_pixel,
+                                    touch.pos_y * points_per_pixel,
+                                ),
+                                force: 0.0,
+                            });
+                            handled = true;
+                        }
+                    }
+
+                    handled
+                }
+                sapp::EventType::TouchesEnded => {
+                    let mut handled = false;
+                    for touch in &event.touches[..event.num_touches as usize] {
+                        if touch.changed {
+                            self.raw_input.events.push(egui::Event::Touch {
+                                device_id: FAKE_TOUCH_DEVICE_ID,
+                                id: egui::TouchId(touch.identifier as _),
+                                phase: egui::TouchPhase::End,
+                                pos: egui::pos2(
+                                    touch.pos_x * points_per_pixel,
+                                    touch.pos_y * points_per_pixel,
+                                ),
+                                force: 0.0,
+                            });
+                            handled = true;
+                        }
+                    }
+
+                    handled
+                }
+                sapp::EventType::TouchesCancelled => {
+                    let mut handled = false;
+                    for touch in &event.touches[..event.num_touches as usize] {
+                        if touch.changed {
+                            self.raw_input.events.push(egui::Event::Touch {
+                                device_id: FAKE_TOUCH_DEVICE_ID,
+                                id: egui::TouchId(touch.identifier as _),
+                                phase: egui::TouchPhase::Cancel,
+                                pos: egui::pos2(
+                                    touch.pos_x * points_per_pixel,
+                                    touch.pos_y * points_per_pixel,
+                                ),
+                                force: 0.0,
+                            });
+                            handled = true;
+                        }
+                    }
+
+                    handled
+                }
+
+                _ => false,
+            };
+
+            /*
+                NOTE: Same as for keys, the app sees every button release and touch end, so that
+                      nothing stays pressed on its side
+            */
+            let is_release = matches!(
+                event._type,
+                sapp::EventType::MouseUp
+                    | sapp::EventType::TouchesEnded
+                    | sapp::EventType::TouchesCancelled
+            );
+
+            was_handled |= is_pointer_event && captures_pointer && !is_release;
+        }
+
+        was_handled |= match event._type {
+            sapp::EventType::Resized => {
+                /*
+                    NOTE: In frame(), we fetch the current size, so there is nothing to do here
+                */
+                false
+            }
+
+            sapp::EventType::Iconified
+            | sapp::EventType::Unfocused
+            | sapp::EventType::Suspended => {
+                /*
+                    NOTE: Modifier keys released while unfocused never send a KeyUp
+                */
+                self.modifier_keys = keyboard::ModifierKeys::default();
+                self.raw_input.modifiers = egui::Modifiers::default();
+                self.raw_input.has_focus = false;
+                false
+            }
+            sapp::EventType::Restored | sapp::EventType::Focused | sapp::EventType::Resumed => {
+                self.raw_input.has_focus = true;
+                false
+            }
+
+            sapp::EventType::QuitRequested => false,
+            sapp::EventType::ClipboardPasted => {
+                self.raw_input
+                    .events
+                    .push(egui::Event::Paste(sapp::get_clipboard_string().to_string()));
+                captures_keyboard
+            }
+            sapp::EventType::FilesDropped => {
+                for index in 0..sapp::get_num_dropped_files() {
+                    let path = sapp::get_dropped_file_path(index);
+                    self.raw_input
+                        .dropped_files
+                        .push(make_dropped_file(path, self.max_dropped_file_read_size));
+                }
+                captures_pointer
+            }
+
+            sapp::EventType::Num => false,
+            sapp::EventType::Invalid => false,
+
+            _ => false,
+        };
+
+        if was_handled && self.consume_events {
+            sapp::consume_event();
+        }
+
+        was_handled
+    }
+
+    /*
+        NOTE: Queries for the game code, they describe the last frame
+    */
+    pub fn is_pointer_over_ui(&self) -> bool {
+        self.context.is_pointer_over_area() || self.context.is_using_pointer()
+    }
+
+    pub fn wants_pointer(&self) -> bool {
+        self.context.wants_pointer_input()
+    }
+
+    pub fn wants_keyboard(&self) -> bool {
+        self.context.wants_keyboard_input()
+    }
+
+    fn captures_pointer(&self) -> bool {
+        match self.input_capture {
+            InputCapture::AlwaysForward => false,
+            InputCapture::WhenEguiWantsInput => self.wants_pointer(),
+            InputCapture::OverEguiArea => self.is_pointer_over_ui(),
+        }
+    }
+
+    fn captures_keyboard(&self) -> bool {
+        match self.input_capture {
+            InputCapture::AlwaysForward => false,
+            InputCapture::WhenEguiWantsInput | InputCapture::OverEguiArea => self.wants_keyboard(),
+        }
+    }
+
+    /*
+        NOTE: Releases the sokol resources, so it has to be called before sg::shutdown(). Dropping
+              the instance does the same
+    */
+    pub fn shutdown(mut self) {
+        self.release();
+    }
+
+    fn release(&mut self) {
+        if self.released {
+            return;
+        }
+        self.released = true;
+
+        #[cfg(feature = "persistence")]
+        self.save();
+
+        if !sg::isvalid() {
+            println!(
+                "segui shutdown(): sokol-gfx was shut down first, can't release its resources"
+            );
+            return;
+        }
+
+        sg::destroy_buffer(self.bindings.vertex_buffers[0]);
+        sg::destroy_buffer(self.bindings.index_buffer);
+        sg::destroy_image(self.white_image);
+        self.textures.destroy_all();
+        sg::destroy_pipeline(self.pipeline);
+        sg::destroy_shader(self.shader);
+
+        #[cfg(feature = "sgl")]
+        if let Some(sgl_renderer) = self.sgl_renderer {
+            sgl_renderer.destroy();
+        }
+    }
+
+    /*
+        NOTE: Applies the texture changes and tessellates the shapes of the latest output once, so
+              that it can be drawn several times (e.g. into the window and an offscreen pass).
+
+              Textures egui wants freed are only freed when the next output is prepared, since the
+              current output may still be drawn again
+    */
+    fn prepare_output(&mut self) {
+        if self.output_prepared {
+            return;
+        }
+        self.output_prepared = true;
+
+        for egui_texture_id in self.textures_to_free.drain(..) {
+            self.textures.free(egui_texture_id);
+        }
+
+        let textures_delta = std::mem::take(&mut self.pending_textures_delta);
+        for (egui_texture_id, texture_delta) in textures_delta.set {
+            self.textures.set(egui_texture_id, &texture_delta);
+        }
+        self.textures.upload_dirty();
+        self.textures_to_free.extend(textures_delta.free);
+
+        let Some(output) = self.latest_output.as_mut() else {
+            return;
+        };
+
+        let shapes = std::mem::take(&mut output.shapes);
+        self.clipped_primitives = self.context.tessellate(shapes);
+
+        self.upload_clipped_primitives();
+    }
+
+    /*
+        NOTE: sokol-gfx only allows a single update per buffer per frame, so every mesh of a frame is
+              appended to the same vertex and index buffer and drawn with its offsets into them
+    */
+    fn upload_clipped_primitives(&mut self) {
+        self.draw_calls.clear();
+        self.stats = Stats {
+            vertex_capacity: self.vertex_capacity,
+            index_capacity: self.index_capacity,
+            num_buffer_resizes: self.stats.num_buffer_resizes,
+            ..Default::default()
+        };
+
+        let upload_to_buffers = self.renderer == Renderer::Gfx;
+
+        if upload_to_buffers {
+            let mut num_vertices = 0;
+            let mut num_indices = 0;
+            for primitive in &self.clipped_primitives {
+                if let egui::epaint::Primitive::Mesh(mesh) = &primitive.primitive {
+                    num_vertices += mesh.vertices.len();
+                    num_indices += mesh.indices.len();
+                }
+            }
+            self.ensure_buffer_capacity(num_vertices, num_indices);
+        }
+
+        for egui::ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in &self.clipped_primitives
+        {
+            let mesh = match primitive {
+                egui::epaint::Primitive::Mesh(mesh) => mesh,
+                egui::epaint::Primitive::Callback(callback) => {
+                    self.draw_calls.push(DrawCall::Callback {
+                        clip_rect: *clip_rect,
+                        callback: callback.clone(),
+                    });
+                    self.stats.num_callbacks += 1;
+                    continue;
+                }
+            };
+
+            if mesh.is_empty() {
+                continue;
+            }
+
+            let vertex_range = sg::slice_as_range(&mesh.vertices);
+            let index_range = sg::slice_as_range(&mesh.indices);
+
+            let mut vertex_buffer_offset = 0;
+            let mut index_buffer_offset = 0;
+
+            if upload_to_buffers {
+                let vertex_buffer = self.bindings.vertex_buffers[0];
+                let index_buffer = self.bindings.index_buffer;
+
+                if sg::query_buffer_will_overflow(vertex_buffer, vertex_range.size)
+                    || sg::query_buffer_will_overflow(index_buffer, index_range.size)
+                {
+                    println!(
+                        "segui draw(): ran out of vertex or index buffer space, skipping mesh"
+                    );
+                    continue;
+                }
+
+                vertex_buffer_offset = sg::append_buffer(vertex_buffer, &vertex_range);
+                index_buffer_offset = sg::append_buffer(index_buffer, &index_range);
+                self.stats.uploaded_bytes += vertex_range.size + index_range.size;
+            }
+
+            self.stats.num_meshes += 1;
+            self.stats.num_vertices += mesh.vertices.len();
+            self.stats.num_indices += mesh.indices.len();
+
+            self.draw_calls.push(DrawCall::Mesh {
+                clip_rect: *clip_rect,
+                texture_id: mesh.texture_id,
+                vertex_buffer_offset,
+                index_buffer_offset,
+                num_elements: mesh.indices.len(),
+            });
+        }
+    }
+
+    /*
+        NOTE: Has to happen before anything is appended in a frame, since the new buffers start out
+              empty
+    */
+    fn ensure_buffer_capacity(&mut self, num_vertices: usize, num_indices: usize) {
+        if num_vertices > self.vertex_capacity {
+            self.vertex_capacity = num_vertices.next_power_of_two();
+            sg::destroy_buffer(self.bindings.vertex_buffers[0]);
+            self.bindings.vertex_buffers[0] = make_vertex_buffer(self.vertex_capacity);
+            self.stats.num_buffer_resizes += 1;
+        }
+
+        if num_indices > self.index_capacity {
+            self.index_capacity = num_indices.next_power_of_two();
+            sg::destroy_buffer(self.bindings.index_buffer);
+            self.bindings.index_buffer = make_index_buffer(self.index_capacity);
+            self.stats.num_buffer_resizes += 1;
+        }
+
+        self.stats.vertex_capacity = self.vertex_capacity;
+        self.stats.index_capacity = self.index_capacity;
+    }
+
+    fn render_clipped_primitives(&mut self, pipeline: sg::Pipeline, width: i32, height: i32) {
+        if self.draw_calls.is_empty() {
+            return;
+        }
+
+        #[cfg(feature = "sgl")]
+        if let Some(sgl_renderer) = self.sgl_renderer {
+            sgl_renderer.render(self, width, height);
+            return;
+        }
+
+        /*
+            NOTE: Positions are in points, the scale maps them onto the pixels of the target
+        */
+        let screen_rect = self.context.screen_rect();
+        let scale_x = width as f32 / screen_rect.width();
+        let scale_y = height as f32 / screen_rect.height();
+
+        let vs_params = shader::VsParams {
+            screen_size: [screen_rect.width(), screen_rect.height()],
+            ..Default::default()
+        };
+
+        let apply_egui_pipeline = || {
+            sg::apply_viewport(0, 0, width, height, true);
+            sg::apply_pipeline(pipeline);
+            sg::apply_uniforms(
+                sg::ShaderStage::Vs,
+                shader::SLOT_VS_PARAMS,
+                &sg::value_as_range(&vs_params),
+            );
+        };
+
+        apply_egui_pipeline();
+
+        for draw_call in &self.draw_calls {
+            match draw_call {
+                DrawCall::Mesh {
+                    clip_rect,
+                    texture_id,
+                    vertex_buffer_offset,
+                    index_buffer_offset,
+                    num_elements,
+                } => {
+                    let Some([x, y, w, h]) =
+                        clip_rect_to_pixels(*clip_rect, scale_x, scale_y, width, height)
+                    else {
+                        continue;
+                    };
+                    sg::apply_scissor_rect(x, y, w, h, true);
+
+                    self.bindings.fs_images[shader::SLOT_TEX] =
+                        self.textures.image(*texture_id).unwrap_or(self.white_image);
+                    self.bindings.vertex_buffer_offsets[0] = *vertex_buffer_offset;
+                    self.bindings.index_buffer_offset = *index_buffer_offset;
+                    sg::apply_bindings(&self.bindings);
+                    sg::draw(0, *num_elements as _, 1);
+                }
+
+                DrawCall::Callback {
+                    clip_rect,
+                    callback,
+                } => {
+                    let Some(scissor) =
+                        clip_rect_to_pixels(*clip_rect, scale_x, scale_y, width, height)
+                    else {
+                        continue;
+                    };
+
+                    let Some(callback_fn) = callback.callback.downcast_ref::<CallbackFn>() else {
+                        println!(
+                            "segui draw(): unknown paint callback payload, expected segui::CallbackFn"
+                        );
+                        continue;
+                    };
+
+                    let viewport = [
+                        (callback.rect.min.x * scale_x).round() as i32,
+                        (callback.rect.min.y * scale_y).round() as i32,
+                        (callback.rect.width() * scale_x).round() as i32,
+                        (callback.rect.height() * scale_y).round() as i32,
+                    ];
+
+                    let info = CallbackInfo {
+                        viewport,
+                        scissor,
+                        pixels_per_point: scale_x,
+                        pass_width: width,
+                        pass_height: height,
+                        rect: callback.rect,
+                        clip_rect: *clip_rect,
+                    };
+
+                    let [x, y, w, h] = viewport;
+                    sg::apply_viewport(x, y, w, h, true);
+                    let [x, y, w, h] = scissor;
+                    sg::apply_scissor_rect(x, y, w, h, true);
+
+                    callback_fn.call(&info);
+
+                    /*
+                        NOTE: The callback may have applied its own pipeline and bindings
+                    */
+                    apply_egui_pipeline();
+                }
+            }
         }
+
+        sg::apply_scissor_rect(0, 0, width, height, true);
     }
 }
 
-impl Default for Desc {
-    fn default() -> Self {
-        Self::new()
+impl Drop for SokolEgui {
+    fn drop(&mut self) {
+        self.release();
     }
 }
 
-#[derive(Clone)]
-#[repr(C)]
-pub struct State {
-    pub context: egui::Context,
-    pub raw_input: egui::RawInput,
-    pub latest_output: Option<egui::FullOutput>,
-    pub gui_cb: Option<extern "C" fn(*const egui::Context)>,
-    pub gui_userdata_cb: Option<extern "C" fn(*const egui::Context, *mut core::ffi::c_void)>,
-    pub userdata: *mut core::ffi::c_void,
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    NotBegun,
+    AlreadyBegun,
+    AlreadyShutDown,
+
+    /*
+        NOTE: The default instance was used again while it was already in use, e.g. from inside
+              a gui callback that runs during frame()
+    */
+    Busy,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::NotBegun => write!(f, "begin() has not been called yet"),
+            Error::AlreadyBegun => write!(f, "begin() was already called, call shutdown() first"),
+            Error::AlreadyShutDown => write!(f, "shutdown() was already called"),
+            Error::Busy => write!(
+                f,
+                "the default instance is already in use further up the stack"
+            ),
+        }
+    }
 }
 
-static mut STATE: std::mem::MaybeUninit<State> = std::mem::MaybeUninit::uninit();
+impl std::error::Error for Error {}
 
-pub extern "C" fn frame() {
-    let state = unsafe { &mut *STATE.as_mut_ptr() };
+/*
+    NOTE: The free functions below drive a default instance, so that they can be passed straight
+          to sokol as callbacks. The try_ versions report misuse of the lifecycle, the others log
+          it and don't do anything.
+
+          While the instance is in use it is moved out of the slot and Busy is left behind, so a
+          call from inside one of its callbacks gets an Error instead of a second &mut. That
+          includes the gui callbacks run by frame(): the helpers below only log Error::Busy there
+          and have to be called from the app's frame callback, before or after frame()
+*/
+enum DefaultInstance {
+    NotBegun,
+    Running(SokolEgui),
+    Busy,
+    ShutDown,
+}
+
+static mut DEFAULT_INSTANCE: DefaultInstance = DefaultInstance::NotBegun;
+
+/*
+    NOTE: Only ever borrowed for the duration of a single swap, never across a call into the
+          instance
+*/
+fn replace_default_instance(new_instance: DefaultInstance) -> DefaultInstance {
+    unsafe { std::mem::replace(&mut *std::ptr::addr_of_mut!(DEFAULT_INSTANCE), new_instance) }
+}
 
-    let mut input = state.raw_input.clone();
-    {
-        input.screen_rect.replace(egui::Rect {
-            max: egui::pos2(sapp::widthf(), sapp::heightf()),
-            min: egui::pos2(0.0, 0.0),
-        });
-        input.pixels_per_point.replace(sapp::dpi_scale());
-        input.predicted_dt = sapp::frame_duration() as _;
+/*
+    NOTE: Puts the instance back into the slot when dropped, also when the closure panics
+*/
+struct DefaultInstanceLease(Option<SokolEgui>);
+
+impl Drop for DefaultInstanceLease {
+    fn drop(&mut self) {
+        if let Some(instance) = self.0.take() {
+            replace_default_instance(DefaultInstance::Running(instance));
+        }
+    }
+}
+
+fn lease_default_instance() -> Result<DefaultInstanceLease, Error> {
+    match replace_default_instance(DefaultInstance::Busy) {
+        DefaultInstance::Running(instance) => Ok(DefaultInstanceLease(Some(instance))),
+        DefaultInstance::NotBegun => {
+            replace_default_instance(DefaultInstance::NotBegun);
+            Err(Error::NotBegun)
+        }
+        DefaultInstance::Busy => Err(Error::Busy),
+        DefaultInstance::ShutDown => {
+            replace_default_instance(DefaultInstance::ShutDown);
+            Err(Error::AlreadyShutDown)
+        }
     }
-    state.context.begin_frame(input);
+}
 
-    if let Some(cb) = state.gui_cb {
-        (cb)(&state.context);
+pub fn with_default_instance<T>(f: impl FnOnce(&mut SokolEgui) -> T) -> Result<T, Error> {
+    let mut lease = lease_default_instance()?;
+    match lease.0.as_mut() {
+        Some(instance) => Ok(f(instance)),
+        None => Err(Error::Busy),
     }
-    if let Some(cb) = state.gui_userdata_cb {
-        (cb)(&state.context, state.userdata);
+}
+
+fn with_default_instance_or_log<T>(
+    function: &str,
+    f: impl FnOnce(&mut SokolEgui) -> T,
+) -> Option<T> {
+    match with_default_instance(f) {
+        Ok(result) => Some(result),
+        Err(error) => {
+            println!("segui {function}(): {error}");
+            None
+        }
     }
+}
 
-    state.raw_input.events.clear();
-    state.raw_input.events.clear();
+pub fn try_begin(desc: &Desc) -> Result<(), Error> {
+    match replace_default_instance(DefaultInstance::Busy) {
+        DefaultInstance::Running(instance) => {
+            replace_default_instance(DefaultInstance::Running(instance));
+            Err(Error::AlreadyBegun)
+        }
+        DefaultInstance::Busy => Err(Error::Busy),
+        DefaultInstance::NotBegun | DefaultInstance::ShutDown => {
+            replace_default_instance(DefaultInstance::Running(SokolEgui::new(desc)));
+            Ok(())
+        }
+    }
+}
 
-    let output = state.context.end_frame();
+pub fn try_frame() -> Result<(), Error> {
+    with_default_instance(|instance| {
+        instance.frame();
+    })
+}
 
-    sapp::set_mouse_cursor(egui_cursor_to_sapp_cursor(
-        output.platform_output.cursor_icon,
-    ));
+pub fn try_draw() -> Result<(), Error> {
+    with_default_instance(|instance| instance.draw())
+}
 
-    // for event in &output.platform_output.events {
-    //     match event {
-    //         egui::output::OutputEvent::Clicked(_) => {}
-    //         egui::output::OutputEvent::DoubleClicked(_) => {}
-    //         egui::output::OutputEvent::TripleClicked(_) => {}
-    //         egui::output::OutputEvent::FocusGained(_) => {}
-    //         egui::output::OutputEvent::TextSelectionChanged(_) => {}
-    //         egui::output::OutputEvent::ValueChanged(_) => {}
-    //     }
-    // }
+pub fn try_event(event: &sapp::Event) -> Result<bool, Error> {
+    with_default_instance(|instance| instance.event(event))
+}
 
-    if let Some(request) = output.platform_output.open_url.as_ref() {
-        /*
-            TODO: Open the url
-        */
-        println!(
-            "segui frame(): should open the url '{}' in new tab: {}",
-            &request.url, request.new_tab
-        );
+pub fn try_shutdown() -> Result<(), Error> {
+    match replace_default_instance(DefaultInstance::ShutDown) {
+        DefaultInstance::Running(instance) => {
+            instance.shutdown();
+            Ok(())
+        }
+        DefaultInstance::NotBegun => {
+            replace_default_instance(DefaultInstance::NotBegun);
+            Err(Error::NotBegun)
+        }
+        DefaultInstance::Busy => {
+            replace_default_instance(DefaultInstance::Busy);
+            Err(Error::Busy)
+        }
+        DefaultInstance::ShutDown => Err(Error::AlreadyShutDown),
     }
+}
 
-    if !output.platform_output.copied_text.is_empty() {
-        /*
-            TODO: Copy the text to clipboard
-        */
-        println!(
-            "segui frame(): should send the text '{}' to system clipboard",
-            &output.platform_output.copied_text
-        );
+pub extern "C" fn begin(desc: &Desc) {
+    if let Err(error) = try_begin(desc) {
+        println!("segui begin(): {error}");
     }
+}
 
-    state.latest_output = Some(output);
+pub extern "C" fn frame() {
+    if let Err(error) = try_frame() {
+        println!("segui frame(): {error}");
+    }
 }
 
 pub extern "C" fn draw() {
-    let state = unsafe { &mut *STATE.as_mut_ptr() };
+    if let Err(error) = try_draw() {
+        println!("segui draw(): {error}");
+    }
+}
 
-    if let Some(output) = state.latest_output.as_ref() {
-        for (egui_texture_id, texture_delta) in &output.textures_delta.set {
-            /*
-                TODO: Update internal textures with the gived deltas
-            */
-            println!(
-                "segui draw(): update texture with id '{egui_texture_id:?}' at '{:?}' with options '{:?}'",
-                texture_delta.pos, texture_delta.options
-            )
+pub extern "C" fn event(event: *const sapp::Event) -> bool {
+    match try_event(unsafe { &*event }) {
+        Ok(was_handled) => was_handled,
+        Err(error) => {
+            println!("segui event(): {error}");
+            false
         }
+    }
+}
 
-        {
-            /*
-                TODO: Render gui using sokol::gfx
-            */
-            // println!("segui draw(): should draw the egui output using sokol::gfx",);
-            // sg::apply_bindings(bindings);
-            // sg::apply_pipeline(pip);
-            // sg::draw(base_element, num_elements, num_instances);
-        }
+pub extern "C" fn shutdown() {
+    if let Err(error) = try_shutdown() {
+        println!("segui shutdown(): {error}");
+    }
+}
 
-        for egui_texture_id in &output.textures_delta.free {
-            /*
-                TODO: Free internal textures with the given ids
-            */
-            println!("segui draw(): free texture with id '{egui_texture_id:?}'",)
-        }
+/*
+    NOTE: Ignored while busy, i.e. from inside the gui callback. Set the next gui before frame()
+*/
+pub fn set_gui(gui: impl FnMut(&egui::Context) + 'static) {
+    with_default_instance_or_log("set_gui", |instance| instance.set_gui(gui));
+}
 
-        if !output.repaint_after.is_zero() {
-            /*
-                TODO: Handle egui wait fo revents mode
-            */
-        }
+/*
+    NOTE: Ignored while busy, call it once after begin()
+*/
+#[cfg(feature = "persistence")]
+pub fn set_persistence_path(path: impl AsRef<std::path::Path>) {
+    with_default_instance_or_log("set_persistence_path", |instance| {
+        instance.set_persistence_path(path)
+    });
+}
+
+/*
+    NOTE: Draws nothing while busy, draw after frame() has returned
+*/
+pub fn draw_to_pass(pass: sg::Pass, pass_action: &sg::PassAction, width: i32, height: i32) {
+    with_default_instance_or_log("draw_to_pass", |instance| {
+        instance.draw_to_pass(pass, pass_action, width, height)
+    });
+}
+
+/*
+    NOTE: Draws nothing while busy, like draw_to_pass()
+*/
+pub fn draw_to_pass_with_pipeline(
+    pipeline: sg::Pipeline,
+    pass: sg::Pass,
+    pass_action: &sg::PassAction,
+    width: i32,
+    height: i32,
+) {
+    with_default_instance_or_log("draw_to_pass_with_pipeline", |instance| {
+        instance.draw_to_pass_with_pipeline(pipeline, pass, pass_action, width, height)
+    });
+}
+
+/*
+    NOTE: Returns an invalid pipeline while busy, make it outside of the gui callback
+*/
+pub fn make_pipeline(desc: &PipelineDesc) -> sg::Pipeline {
+    with_default_instance_or_log("make_pipeline", |instance| instance.make_pipeline(desc))
+        .unwrap_or_else(sg::Pipeline::new)
+}
+
+/*
+    NOTE: Always false while busy, use egui::Context::is_pointer_over_area() in the gui callback
+*/
+pub fn is_pointer_over_ui() -> bool {
+    with_default_instance_or_log("is_pointer_over_ui", |instance| {
+        instance.is_pointer_over_ui()
+    })
+    .unwrap_or(false)
+}
+
+/*
+    NOTE: Always false while busy, use egui::Context::wants_keyboard_input() in the gui callback
+*/
+pub fn wants_keyboard() -> bool {
+    with_default_instance_or_log("wants_keyboard", |instance| instance.wants_keyboard())
+        .unwrap_or(false)
+}
+
+/*
+    NOTE: Returns empty Stats while busy
+*/
+pub fn stats() -> Stats {
+    with_default_instance_or_log("stats", |instance| instance.stats()).unwrap_or_default()
+}
+
+/*
+    NOTE: Returns None while busy, register textures before frame() and keep the ids for the gui
+*/
+pub fn register_user_texture(image: sg::Image) -> Option<egui::TextureId> {
+    with_default_instance_or_log("register_user_texture", |instance| {
+        instance.register_user_texture(image)
+    })
+}
+
+/*
+    NOTE: Returns false while busy
+*/
+pub fn replace_user_texture(texture_id: egui::TextureId, image: sg::Image) -> bool {
+    with_default_instance_or_log("replace_user_texture", |instance| {
+        instance.replace_user_texture(texture_id, image)
+    })
+    .unwrap_or(false)
+}
+
+/*
+    NOTE: Returns None while busy and the texture stays registered
+*/
+pub fn unregister_user_texture(texture_id: egui::TextureId) -> Option<sg::Image> {
+    with_default_instance_or_log("unregister_user_texture", |instance| {
+        instance.unregister_user_texture(texture_id)
+    })
+    .flatten()
+}
+
+/*
+    NOTE: Copy and paste only work with the clipboard enabled in the sapp::Desc, and anything
+          longer than clipboard_size is cut off
+*/
+pub const MIN_CLIPBOARD_SIZE: i32 = 8192;
+
+fn check_clipboard_desc() {
+    if !sapp::isvalid() {
+        return;
+    }
+
+    let app_desc = sapp::query_desc();
+    if !app_desc.enable_clipboard {
+        println!("segui begin(): sapp::Desc::enable_clipboard is false, copy and paste won't work");
+    } else if app_desc.clipboard_size < MIN_CLIPBOARD_SIZE {
+        println!(
+            "segui begin(): sapp::Desc::clipboard_size is {}, copied text longer than that is cut off (at least {MIN_CLIPBOARD_SIZE} is recommended)",
+            app_desc.clipboard_size
+        );
     }
-    /*
-        TODO: else { something is wrong, log? }
-    */
 }
 
-pub extern "C" fn begin(desc: &Desc) {
-    /*
-        TODO: Initialize required pipeline for rendering with gfx
-    */
+/*
+    NOTE: On the web the path is only the name of the file and its content has to be fetched with
+          sapp::html5_fetch_dropped_file(), so nothing is read there
+*/
+fn make_dropped_file(path: &str, max_read_size: usize) -> egui::DroppedFile {
+    let path = std::path::PathBuf::from(path);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
 
-    let initial_state = State {
-        context: egui::Context::default(),
-        raw_input: egui::RawInput::default(),
-        latest_output: None,
+    let metadata = std::fs::metadata(&path).ok();
+    let last_modified = metadata
+        .as_ref()
+        .and_then(|metadata| metadata.modified().ok());
 
-        gui_cb: desc.gui_cb,
-        gui_userdata_cb: desc.gui_userdata_cb,
-        userdata: desc.userdata,
+    let bytes = match metadata {
+        Some(metadata) if max_read_size > 0 && metadata.len() <= max_read_size as u64 => {
+            match std::fs::read(&path) {
+                Ok(bytes) => Some(bytes.into()),
+                Err(error) => {
+                    println!(
+                        "segui event(): failed to read dropped file '{}': {error}",
+                        path.display()
+                    );
+                    None
+                }
+            }
+        }
+        _ => None,
     };
 
-    unsafe {
-        STATE.write(initial_state);
+    egui::DroppedFile {
+        path: Some(path),
+        name,
+        last_modified,
+        bytes,
     }
 }
 
-pub extern "C" fn shutdown() {
-    unsafe { STATE.assume_init_drop() }
+fn make_vertex_buffer(capacity: usize) -> sg::Buffer {
+    sg::make_buffer(&sg::BufferDesc {
+        size: capacity * std::mem::size_of::<egui::epaint::Vertex>(),
+        _type: sg::BufferType::Vertexbuffer,
+        usage: sg::Usage::Stream,
+        label: b"segui-vertices\0".as_ptr() as _,
+        ..Default::default()
+    })
+}
+
+fn make_index_buffer(capacity: usize) -> sg::Buffer {
+    sg::make_buffer(&sg::BufferDesc {
+        size: capacity * std::mem::size_of::<u32>(),
+        _type: sg::BufferType::Indexbuffer,
+        usage: sg::Usage::Stream,
+        label: b"segui-indices\0".as_ptr() as _,
+        ..Default::default()
+    })
+}
+
+/*
+    NOTE: Transforms a clip rect from points to pixels and clamps it to the framebuffer
+*/
+fn clip_rect_to_pixels(
+    clip_rect: egui::Rect,
+    scale_x: f32,
+    scale_y: f32,
+    width: i32,
+    height: i32,
+) -> Option<[i32; 4]> {
+    let min_x = (clip_rect.min.x * scale_x).round().clamp(0.0, width as f32);
+    let min_y = (clip_rect.min.y * scale_y)
+        .round()
+        .clamp(0.0, height as f32);
+    let max_x = (clip_rect.max.x * scale_x)
+        .round()
+        .clamp(min_x, width as f32);
+    let max_y = (clip_rect.max.y * scale_y)
+        .round()
+        .clamp(min_y, height as f32);
+
+    if max_x <= min_x || max_y <= min_y {
+        return None;
+    }
+
+    Some([
+        min_x as i32,
+        min_y as i32,
+        (max_x - min_x) as i32,
+        (max_y - min_y) as i32,
+    ])
+}
+
+fn make_egui_pipeline(shader: sg::Shader, desc: &PipelineDesc) -> sg::Pipeline {
+    let mut pipeline_desc = sg::PipelineDesc::new();
+    pipeline_desc.shader = shader;
+    pipeline_desc.layout.buffers[0].stride = std::mem::size_of::<egui::epaint::Vertex>() as _;
+    pipeline_desc.layout.attrs[shader::ATTR_VS_IN_POSITION].format = sg::VertexFormat::Float2;
+    pipeline_desc.layout.attrs[shader::ATTR_VS_IN_UV].format = sg::VertexFormat::Float2;
+    pipeline_desc.layout.attrs[shader::ATTR_VS_IN_COLOR].format = sg::VertexFormat::Ubyte4n;
+    pipeline_desc.index_type = sg::IndexType::Uint32;
+    pipeline_desc.cull_mode = sg::CullMode::None;
+    pipeline_desc.depth.pixel_format = desc.depth_format;
+    pipeline_desc.depth.write_enabled = false;
+    pipeline_desc.depth.compare = sg::CompareFunc::Always;
+    pipeline_desc.sample_count = desc.sample_count;
+    pipeline_desc.colors[0].pixel_format = desc.color_format;
+
+    pipeline_desc.colors[0].blend = egui_blend_state(desc.blend_mode);
+    pipeline_desc.label = b"segui-pipeline\0".as_ptr() as _;
+
+    sg::make_pipeline(&pipeline_desc)
+}
+
+/*
+    NOTE: Shared by our pipelines and the sgl one
+*/
+pub(crate) fn egui_blend_state(blend_mode: BlendMode) -> sg::BlendState {
+    let src_factor_rgb = match blend_mode {
+        BlendMode::Premultiplied => sg::BlendFactor::One,
+    };
+
+    sg::BlendState {
+        enabled: true,
+        src_factor_rgb,
+        dst_factor_rgb: sg::BlendFactor::OneMinusSrcAlpha,
+        op_rgb: sg::BlendOp::Add,
+        src_factor_alpha: sg::BlendFactor::OneMinusDstAlpha,
+        dst_factor_alpha: sg::BlendFactor::One,
+        op_alpha: sg::BlendOp::Add,
+    }
 }
 
 #[rustfmt::skip]
@@ -223,374 +1690,52 @@ pub fn sapp_mouse_button_to_egui_pointer_button(button: sapp::Mousebutton) -> eg
     }
 }
 
-#[rustfmt::skip]
-pub fn sapp_key_to_egui_key(keycode: sapp::Keycode) -> Option<egui::Key> {
-    match keycode {
-        sapp::Keycode::Space        => Some(egui::Key::Space),
-        sapp::Keycode::Minus        => Some(egui::Key::Minus),
-        sapp::Keycode::Num0         => Some(egui::Key::Num0),
-        sapp::Keycode::Num1         => Some(egui::Key::Num1),
-        sapp::Keycode::Num2         => Some(egui::Key::Num2),
-        sapp::Keycode::Num3         => Some(egui::Key::Num3),
-        sapp::Keycode::Num4         => Some(egui::Key::Num4),
-        sapp::Keycode::Num5         => Some(egui::Key::Num5),
-        sapp::Keycode::Num6         => Some(egui::Key::Num6),
-        sapp::Keycode::Num7         => Some(egui::Key::Num7),
-        sapp::Keycode::Num8         => Some(egui::Key::Num8),
-        sapp::Keycode::Num9         => Some(egui::Key::Num9),
-        sapp::Keycode::Equal        => Some(egui::Key::PlusEquals),
-        sapp::Keycode::A            => Some(egui::Key::A),
-        sapp::Keycode::B            => Some(egui::Key::B),
-        sapp::Keycode::C            => Some(egui::Key::C),
-        sapp::Keycode::D            => Some(egui::Key::D),
-        sapp::Keycode::E            => Some(egui::Key::E),
-        sapp::Keycode::F            => Some(egui::Key::F),
-        sapp::Keycode::G            => Some(egui::Key::G),
-        sapp::Keycode::H            => Some(egui::Key::H),
-        sapp::Keycode::I            => Some(egui::Key::I),
-        sapp::Keycode::J            => Some(egui::Key::J),
-        sapp::Keycode::K            => Some(egui::Key::K),
-        sapp::Keycode::L            => Some(egui::Key::L),
-        sapp::Keycode::M            => Some(egui::Key::M),
-        sapp::Keycode::N            => Some(egui::Key::N),
-        sapp::Keycode::O            => Some(egui::Key::O),
-        sapp::Keycode::P            => Some(egui::Key::P),
-        sapp::Keycode::Q            => Some(egui::Key::Q),
-        sapp::Keycode::R            => Some(egui::Key::R),
-        sapp::Keycode::S            => Some(egui::Key::S),
-        sapp::Keycode::T            => Some(egui::Key::T),
-        sapp::Keycode::U            => Some(egui::Key::U),
-        sapp::Keycode::V            => Some(egui::Key::V),
-        sapp::Keycode::W            => Some(egui::Key::W),
-        sapp::Keycode::X            => Some(egui::Key::X),
-        sapp::Keycode::Y            => Some(egui::Key::Y),
-        sapp::Keycode::Z            => Some(egui::Key::Z),
-        sapp::Keycode::Escape       => Some(egui::Key::Escape),
-        sapp::Keycode::Enter        => Some(egui::Key::Enter),
-        sapp::Keycode::Tab          => Some(egui::Key::Tab),
-        sapp::Keycode::Backspace    => Some(egui::Key::Backspace),
-        sapp::Keycode::Insert       => Some(egui::Key::Insert),
-        sapp::Keycode::Delete       => Some(egui::Key::Delete),
-        sapp::Keycode::Right        => Some(egui::Key::ArrowRight),
-        sapp::Keycode::Left         => Some(egui::Key::ArrowLeft),
-        sapp::Keycode::Down         => Some(egui::Key::ArrowDown),
-        sapp::Keycode::Up           => Some(egui::Key::ArrowUp),
-        sapp::Keycode::PageUp       => Some(egui::Key::PageUp),
-        sapp::Keycode::PageDown     => Some(egui::Key::PageDown),
-        sapp::Keycode::Home         => Some(egui::Key::Home),
-        sapp::Keycode::End          => Some(egui::Key::End),
-        sapp::Keycode::F1           => Some(egui::Key::F1),
-        sapp::Keycode::F2           => Some(egui::Key::F2),
-        sapp::Keycode::F3           => Some(egui::Key::F3),
-        sapp::Keycode::F4           => Some(egui::Key::F4),
-        sapp::Keycode::F5           => Some(egui::Key::F5),
-        sapp::Keycode::F6           => Some(egui::Key::F6),
-        sapp::Keycode::F7           => Some(egui::Key::F7),
-        sapp::Keycode::F8           => Some(egui::Key::F8),
-        sapp::Keycode::F9           => Some(egui::Key::F9),
-        sapp::Keycode::F10          => Some(egui::Key::F10),
-        sapp::Keycode::F11          => Some(egui::Key::F11),
-        sapp::Keycode::F12          => Some(egui::Key::F12),
-        sapp::Keycode::F13          => Some(egui::Key::F13),
-        sapp::Keycode::F14          => Some(egui::Key::F14),
-        sapp::Keycode::F15          => Some(egui::Key::F15),
-        sapp::Keycode::F16          => Some(egui::Key::F16),
-        sapp::Keycode::F17          => Some(egui::Key::F17),
-        sapp::Keycode::F18          => Some(egui::Key::F18),
-        sapp::Keycode::F19          => Some(egui::Key::F19),
-        sapp::Keycode::F20          => Some(egui::Key::F20),
-        sapp::Keycode::Kp0          => Some(egui::Key::Num0),
-        sapp::Keycode::Kp1          => Some(egui::Key::Num1),
-        sapp::Keycode::Kp2          => Some(egui::Key::Num2),
-        sapp::Keycode::Kp3          => Some(egui::Key::Num3),
-        sapp::Keycode::Kp4          => Some(egui::Key::Num4),
-        sapp::Keycode::Kp5          => Some(egui::Key::Num5),
-        sapp::Keycode::Kp6          => Some(egui::Key::Num6),
-        sapp::Keycode::Kp7          => Some(egui::Key::Num7),
-        sapp::Keycode::Kp8          => Some(egui::Key::Num8),
-        sapp::Keycode::Kp9          => Some(egui::Key::Num9),
-        sapp::Keycode::KpSubtract   => Some(egui::Key::Minus),
-        sapp::Keycode::KpAdd        => Some(egui::Key::PlusEquals),
-        sapp::Keycode::KpEnter      => Some(egui::Key::Enter),
-        sapp::Keycode::KpEqual      => Some(egui::Key::PlusEquals),
-
-        // sapp::Keycode::F21          => Some(egui::Key::F21),
-        // sapp::Keycode::F22          => Some(egui::Key::F22),
-        // sapp::Keycode::F23          => Some(egui::Key::F23),
-        // sapp::Keycode::F24          => Some(egui::Key::F24),
-        // sapp::Keycode::F25          => Some(egui::Key::F25),
-        // sapp::Keycode::Apostrophe   => Some(egui::Key::Apostrophe),
-        // sapp::Keycode::Invalid      => Some(egui::Key::Invalid),
-        // sapp::Keycode::Comma        => Some(egui::Key::Comma),
-        // sapp::Keycode::Period       => Some(egui::Key::Period),
-        // sapp::Keycode::Slash        => Some(egui::Key::Slash),
-        // sapp::Keycode::Semicolon    => Some(egui::Key::Semicolon),
-        // sapp::Keycode::LeftBracket  => Some(egui::Key::LeftBracket),
-        // sapp::Keycode::Backslash    => Some(egui::Key::Backslash),
-        // sapp::Keycode::RightBracket => Some(egui::Key::RightBracket),
-        // sapp::Keycode::GraveAccent  => Some(egui::Key::GraveAccent),
-        // sapp::Keycode::World1       => Some(egui::Key::World1),
-        // sapp::Keycode::World2       => Some(egui::Key::World2),
-        // sapp::Keycode::CapsLock     => Some(egui::Key::CapsLock),
-        // sapp::Keycode::ScrollLock   => Some(egui::Key::ScrollLock),
-        // sapp::Keycode::NumLock      => Some(egui::Key::NumLock),
-        // sapp::Keycode::PrintScreen  => Some(egui::Key::PrintScreen),
-        // sapp::Keycode::Pause        => Some(egui::Key::Pause),
-        // sapp::Keycode::Menu         => Some(egui::Key::Menu),
-        // sapp::Keycode::LeftAlt      => Some(egui::Key::LeftAlt),
-        // sapp::Keycode::KpDivide     => Some(egui::Key::KpDivide),
-        // sapp::Keycode::RightAlt     => Some(egui::Key::RightAlt),
-        // sapp::Keycode::KpDecimal    => Some(egui::Key::KpDecimal),
-        // sapp::Keycode::LeftShift    => Some(egui::Key::LeftShift),
-        // sapp::Keycode::LeftSuper    => Some(egui::Key::LeftSuper),
-        // sapp::Keycode::KpMultiply   => Some(egui::Key::Multiply),
-        // sapp::Keycode::RightSuper   => Some(egui::Key::RightSuper),
-        // sapp::Keycode::RightShift   => Some(egui::Key::RightShift),
-        // sapp::Keycode::LeftControl  => Some(egui::Key::LeftControl),
-        // sapp::Keycode::RightControl => Some(egui::Key::RightControl),
-
-        _ => None,
-    }
-}
-
-pub extern "C" fn event(event: *const sapp::Event) -> bool {
-    let state = unsafe { &mut *STATE.as_mut_ptr() };
-    let event = unsafe { *event };
-
-    let mut was_handled = false;
-
-    if state.context.wants_keyboard_input() {
-        was_handled |= match event._type {
-            sapp::EventType::KeyDown => {
-                #[rustfmt::skip]
-                let mut was_handled = {
-                    /*
-                        TODO: Verify translation of event.modifiers
-                    */
-                    state.raw_input.modifiers.alt     = (event.modifiers | sapp::MODIFIER_ALT   as u32) != 0;
-                    state.raw_input.modifiers.ctrl    = (event.modifiers | sapp::MODIFIER_CTRL  as u32) != 0;
-                    state.raw_input.modifiers.shift   = (event.modifiers | sapp::MODIFIER_SHIFT as u32) != 0;
-                    state.raw_input.modifiers.command = (event.modifiers | sapp::MODIFIER_CTRL  as u32) != 0
-                                                     || (event.modifiers | sapp::MODIFIER_SUPER as u32) != 0;
-                    true
-                };
-
-                if let Some(key) = sapp_key_to_egui_key(event.key_code) {
-                    state.raw_input.events.push(egui::Event::Key {
-                        key,
-                        pressed: false,
-                        repeat: event.key_repeat,
-                        modifiers: state.raw_input.modifiers.clone(),
-                    });
-                    was_handled = true;
-                }
-
-                was_handled
-            }
-
-            sapp::EventType::KeyUp => {
-                #[rustfmt::skip]
-                let mut was_handled = {
-                    /*
-                        TODO: Verify translation of event.modifiers
-                    */
-                    state.raw_input.modifiers.alt     = (event.modifiers | sapp::MODIFIER_ALT   as u32) != 0;
-                    state.raw_input.modifiers.ctrl    = (event.modifiers | sapp::MODIFIER_CTRL  as u32) != 0;
-                    state.raw_input.modifiers.shift   = (event.modifiers | sapp::MODIFIER_SHIFT as u32) != 0;
-                    state.raw_input.modifiers.command = (event.modifiers | sapp::MODIFIER_CTRL  as u32) != 0
-                                                     || (event.modifiers | sapp::MODIFIER_SUPER as u32) != 0;
-
-                    true
-                };
-
-                if let Some(key) = sapp_key_to_egui_key(event.key_code) {
-                    state.raw_input.events.push(egui::Event::Key {
-                        key,
-                        pressed: false,
-                        repeat: event.key_repeat,
-                        modifiers: state.raw_input.modifiers.clone(),
-                    });
-                    was_handled = true;
-                }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                was_handled
-            }
+    /*
+        NOTE: The only test touching the default instance, so the tests running in parallel don't
+              see each other's state. No instance is ever created, which would need sokol
+    */
+    #[test]
+    fn default_instance_helpers_fail_while_busy() {
+        replace_default_instance(DefaultInstance::Busy);
 
-            sapp::EventType::Char => {
-                if let Some(char) = char::from_u32(event.char_code) {
-                    state
-                        .raw_input
-                        .events
-                        .push(egui::Event::Text(char.to_string()));
-                    true
-                } else {
-                    false
-                }
-            }
+        assert_eq!(with_default_instance(|_| ()).err(), Some(Error::Busy));
+        assert_eq!(try_frame(), Err(Error::Busy));
+        assert_eq!(try_draw(), Err(Error::Busy));
+        assert_eq!(try_begin(&Desc::default()), Err(Error::Busy));
+        assert_eq!(try_shutdown(), Err(Error::Busy));
 
-            _ => false,
-        }
-    }
+        set_gui(|_| {});
+        assert!(!is_pointer_over_ui());
+        assert!(!wants_keyboard());
+        assert_eq!(make_pipeline(&PipelineDesc::new()).id, 0);
+        assert_eq!(register_user_texture(sg::Image::new()), None);
+        assert!(!replace_user_texture(
+            egui::TextureId::User(0),
+            sg::Image::new()
+        ));
+        assert!(unregister_user_texture(egui::TextureId::User(0)).is_none());
 
-    if true | state.context.wants_pointer_input() {
         /*
-            TODO: Is this valid? Is there an equivalent of TouchDeviceId in sapp?
+            NOTE: None of the failed calls may take the slot out of the Busy state
         */
-        const FAKE_TOUCH_DEVICE_ID: egui::TouchDeviceId = egui::TouchDeviceId(1337);
-
-        was_handled |= match event._type {
-            sapp::EventType::MouseDown => {
-                state.raw_input.events.push(egui::Event::PointerButton {
-                    pos: egui::pos2(event.mouse_x, event.mouse_y),
-                    button: sapp_mouse_button_to_egui_pointer_button(event.mouse_button),
-                    pressed: false,
-                    modifiers: state.raw_input.modifiers.clone(),
-                });
-                true
-            }
-            sapp::EventType::MouseUp => {
-                state.raw_input.events.push(egui::Event::PointerButton {
-                    pos: egui::pos2(event.mouse_x, event.mouse_y),
-                    button: sapp_mouse_button_to_egui_pointer_button(event.mouse_button),
-                    pressed: true,
-                    modifiers: state.raw_input.modifiers.clone(),
-                });
-                true
-            }
-            sapp::EventType::MouseScroll => {
-                state.raw_input.events.push(egui::Event::Scroll(egui::vec2(
-                    event.scroll_x,
-                    event.scroll_y,
-                )));
-                true
-            }
-
-            sapp::EventType::MouseMove => {
-                state
-                    .raw_input
-                    .events
-                    .push(egui::Event::PointerMoved(egui::pos2(
-                        event.mouse_x,
-                        event.mouse_y,
-                    )));
-                true
-            }
-
-            sapp::EventType::MouseEnter => {
-                state
-                    .raw_input
-                    .events
-                    .push(egui::Event::PointerMoved(egui::pos2(
-                        event.mouse_x,
-                        event.mouse_y,
-                    )));
-                true
-            }
-            sapp::EventType::MouseLeave => {
-                state.raw_input.events.push(egui::Event::PointerGone);
-                true
-            }
-
-            sapp::EventType::TouchesBegan => {
-                let mut handled = false;
-                for touch in &event.touches[..event.num_touches as usize] {
-                    if touch.changed {
-                        state.raw_input.events.push(egui::Event::Touch {
-                            device_id: FAKE_TOUCH_DEVICE_ID,
-                            id: egui::TouchId(touch.identifier as _),
-                            phase: egui::TouchPhase::Start,
-                            pos: egui::pos2(touch.pos_x, touch.pos_y),
-                            force: 0.0,
-                        });
-                        handled = true;
-                    }
-                }
-
-                handled
-            }
-            sapp::EventType::TouchesMoved => {
-                let mut handled = false;
-                for touch in &event.touches[..event.num_touches as usize] {
-                    if touch.changed {
-                        state.raw_input.events.push(egui::Event::Touch {
-                            device_id: FAKE_TOUCH_DEVICE_ID,
-                            id: egui::TouchId(touch.identifier as _),
-                            phase: egui::TouchPhase::Move,
-                            pos: egui::pos2(touch.pos_x, touch.pos_y),
-                            force: 0.0,
-                        });
-                        handled = true;
-                    }
-                }
-
-                handled
-            }
-            sapp::EventType::TouchesEnded => {
-                let mut handled = false;
-                for touch in &event.touches[..event.num_touches as usize] {
-                    if touch.changed {
-                        state.raw_input.events.push(egui::Event::Touch {
-                            device_id: FAKE_TOUCH_DEVICE_ID,
-                            id: egui::TouchId(touch.identifier as _),
-                            phase: egui::TouchPhase::End,
-                            pos: egui::pos2(touch.pos_x, touch.pos_y),
-                            force: 0.0,
-                        });
-                        handled = true;
-                    }
-                }
-
-                handled
-            }
-            sapp::EventType::TouchesCancelled => {
-                let mut handled = false;
-                for touch in &event.touches[..event.num_touches as usize] {
-                    if touch.changed {
-                        state.raw_input.events.push(egui::Event::Touch {
-                            device_id: FAKE_TOUCH_DEVICE_ID,
-                            id: egui::TouchId(touch.identifier as _),
-                            phase: egui::TouchPhase::Cancel,
-                            pos: egui::pos2(touch.pos_x, touch.pos_y),
-                            force: 0.0,
-                        });
-                        handled = true;
-                    }
-                }
+        assert!(matches!(
+            replace_default_instance(DefaultInstance::NotBegun),
+            DefaultInstance::Busy
+        ));
 
-                handled
-            }
+        assert_eq!(try_frame(), Err(Error::NotBegun));
+        assert_eq!(try_shutdown(), Err(Error::NotBegun));
+        assert!(matches!(
+            replace_default_instance(DefaultInstance::ShutDown),
+            DefaultInstance::NotBegun
+        ));
 
-            _ => false,
-        }
+        assert_eq!(try_draw(), Err(Error::AlreadyShutDown));
+        assert_eq!(try_shutdown(), Err(Error::AlreadyShutDown));
+        replace_default_instance(DefaultInstance::NotBegun);
     }
-
-    was_handled |= match event._type {
-        sapp::EventType::Resized => {
-            /*
-                NOTE: In frame(), we fetch the current size, so we will pretend that we are handling
-                      since the effect is the same
-            */
-            true
-        }
-
-        sapp::EventType::Iconified | sapp::EventType::Unfocused | sapp::EventType::Suspended => {
-            state.raw_input.has_focus = false;
-            true
-        }
-        sapp::EventType::Restored | sapp::EventType::Focused | sapp::EventType::Resumed => {
-            state.raw_input.has_focus = true;
-            true
-        }
-
-        sapp::EventType::QuitRequested => false,
-        sapp::EventType::ClipboardPasted => false,
-        sapp::EventType::FilesDropped => false,
-
-        sapp::EventType::Num => false,
-        sapp::EventType::Invalid => false,
-
-        _ => false,
-    };
-
-    was_handled
 }
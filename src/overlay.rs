@@ -0,0 +1,194 @@
+use sokol::app as sapp;
+use std::cell::Cell;
+use std::ffi::c_void;
+
+use crate::{Desc, InputCapture, SokolEgui};
+
+struct Overlay {
+    app_desc: sapp::Desc,
+    desc: Desc,
+    gui: Box<dyn FnMut(&egui::Context)>,
+    segui: Option<SokolEgui>,
+    drawn: bool,
+    reported_missing_draw: bool,
+}
+
+thread_local! {
+    /*
+        NOTE: Only set while the app's frame callback runs, so draw_overlay() can find the overlay
+    */
+    static CURRENT_OVERLAY: Cell<*mut Overlay> = const { Cell::new(std::ptr::null_mut()) };
+}
+
+/*
+    NOTE: Wraps the callbacks of an existing app, so egui can be put on top of it. The only change
+          the app needs is a call to draw_overlay() in its default pass, before sg::commit().
+          Use the returned Desc with sapp::run()
+*/
+pub fn overlay(app_desc: &sapp::Desc, gui: impl FnMut(&egui::Context) + 'static) -> sapp::Desc {
+    let desc = Desc {
+        input_capture: InputCapture::OverEguiArea,
+        ..Default::default()
+    };
+    overlay_with_desc(app_desc, &desc, gui)
+}
+
+pub fn overlay_with_desc(
+    app_desc: &sapp::Desc,
+    desc: &Desc,
+    gui: impl FnMut(&egui::Context) + 'static,
+) -> sapp::Desc {
+    let overlay = Box::new(Overlay {
+        app_desc: *app_desc,
+        desc: *desc,
+        gui: Box::new(gui),
+        segui: None,
+        drawn: false,
+        reported_missing_draw: false,
+    });
+
+    sapp::Desc {
+        init_cb: None,
+        frame_cb: None,
+        event_cb: None,
+        cleanup_cb: None,
+        init_userdata_cb: Some(init),
+        frame_userdata_cb: Some(frame),
+        event_userdata_cb: Some(event),
+        cleanup_userdata_cb: Some(cleanup),
+        user_data: Box::into_raw(overlay) as *mut c_void,
+        ..*app_desc
+    }
+}
+
+fn overlay_from_userdata<'a>(userdata: *mut c_void) -> &'a mut Overlay {
+    unsafe { &mut *(userdata as *mut Overlay) }
+}
+
+/*
+    NOTE: The app sets up sokol-gfx in its init, so segui can only begin afterwards
+*/
+extern "C" fn init(userdata: *mut c_void) {
+    let overlay = overlay_from_userdata(userdata);
+
+    if let Some(cb) = overlay.app_desc.init_cb {
+        (cb)();
+    }
+    if let Some(cb) = overlay.app_desc.init_userdata_cb {
+        (cb)(overlay.app_desc.user_data);
+    }
+
+    overlay.segui = Some(SokolEgui::new(&overlay.desc));
+}
+
+/*
+    NOTE: Draws egui into the pass that is currently open. Call it from the app's frame callback,
+          inside its default pass and before sg::commit(). Does nothing outside of an overlay
+          frame or when egui was already drawn this frame
+*/
+pub fn draw_overlay() {
+    let overlay = CURRENT_OVERLAY.with(|current| current.get());
+    if overlay.is_null() {
+        println!("segui draw_overlay(): not called from the frame callback of an overlay app");
+        return;
+    }
+
+    /*
+        NOTE: frame() doesn't hold a reference to the overlay while the app's callback runs
+    */
+    let overlay = unsafe { &mut *overlay };
+    if overlay.drawn {
+        return;
+    }
+
+    if let Some(segui) = overlay.segui.as_mut() {
+        segui.draw();
+        overlay.drawn = true;
+    }
+}
+
+/*
+    NOTE: The app has to call draw_overlay() inside its default pass. Drawing egui afterwards
+          would need a second pass and commit, which only keeps the app's contents on GL
+          without MSAA, so a missing call is reported instead
+*/
+extern "C" fn frame(userdata: *mut c_void) {
+    let (frame_cb, frame_userdata_cb, user_data) = {
+        let overlay = overlay_from_userdata(userdata);
+
+        if let Some(segui) = overlay.segui.as_mut() {
+            let gui = &mut overlay.gui;
+            segui.frame_with(|ctx| (gui)(ctx));
+        }
+        overlay.drawn = false;
+
+        (
+            overlay.app_desc.frame_cb,
+            overlay.app_desc.frame_userdata_cb,
+            overlay.app_desc.user_data,
+        )
+    };
+
+    CURRENT_OVERLAY.with(|current| current.set(userdata as *mut Overlay));
+    if let Some(cb) = frame_cb {
+        (cb)();
+    }
+    if let Some(cb) = frame_userdata_cb {
+        (cb)(user_data);
+    }
+    CURRENT_OVERLAY.with(|current| current.set(std::ptr::null_mut()));
+
+    let overlay = overlay_from_userdata(userdata);
+    if overlay.drawn || overlay.segui.is_none() {
+        return;
+    }
+
+    if !overlay.reported_missing_draw {
+        overlay.reported_missing_draw = true;
+        println!(
+            "segui overlay(): draw_overlay() wasn't called from the app's frame callback, egui isn't drawn"
+        );
+    }
+    debug_assert!(
+        overlay.drawn,
+        "segui overlay(): draw_overlay() has to be called inside the app's default pass"
+    );
+}
+
+extern "C" fn event(event: *const sapp::Event, userdata: *mut c_void) {
+    let overlay = overlay_from_userdata(userdata);
+
+    /*
+        NOTE: Events egui handled according to the InputCapture of the Desc are kept from the app
+    */
+    if let Some(segui) = overlay.segui.as_mut() {
+        if segui.event(unsafe { &*event }) {
+            return;
+        }
+    }
+
+    if let Some(cb) = overlay.app_desc.event_cb {
+        (cb)(event);
+    }
+    if let Some(cb) = overlay.app_desc.event_userdata_cb {
+        (cb)(event, overlay.app_desc.user_data);
+    }
+}
+
+/*
+    NOTE: segui has to release its resources before the app calls sg::shutdown()
+*/
+extern "C" fn cleanup(userdata: *mut c_void) {
+    let mut overlay = unsafe { Box::from_raw(userdata as *mut Overlay) };
+
+    if let Some(segui) = overlay.segui.take() {
+        segui.shutdown();
+    }
+
+    if let Some(cb) = overlay.app_desc.cleanup_cb {
+        (cb)();
+    }
+    if let Some(cb) = overlay.app_desc.cleanup_userdata_cb {
+        (cb)(overlay.app_desc.user_data);
+    }
+}
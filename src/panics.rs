@@ -0,0 +1,84 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::panic::AssertUnwindSafe;
+
+#[derive(Debug, Clone)]
+pub struct GuiPanic {
+    pub message: String,
+    pub backtrace: String,
+}
+
+thread_local! {
+    static LAST_BACKTRACE: RefCell<Option<String>> = const { RefCell::new(None) };
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+static INSTALL_HOOK: std::sync::Once = std::sync::Once::new();
+
+/*
+    NOTE: The backtrace is only available inside the panic hook, so ours records it before handing
+          the panic on to whatever hook was installed before. Capturing is slow, so it only
+          happens for panics inside catch(), all others go straight to the previous hook
+*/
+pub fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if CATCHING.with(|catching| catching.get()) {
+                let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+                LAST_BACKTRACE
+                    .with(|last_backtrace| *last_backtrace.borrow_mut() = Some(backtrace));
+            }
+            previous_hook(info);
+        }));
+    });
+}
+
+pub fn catch(f: impl FnOnce()) -> Option<(GuiPanic, Box<dyn Any + Send>)> {
+    let was_catching = CATCHING.with(|catching| catching.replace(true));
+    let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(was_catching));
+
+    let payload = result.err()?;
+
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    };
+    let backtrace = LAST_BACKTRACE
+        .with(|last_backtrace| last_backtrace.borrow_mut().take())
+        .unwrap_or_default();
+
+    Some((GuiPanic { message, backtrace }, payload))
+}
+
+/*
+    NOTE: Returns true when the window was dismissed
+*/
+pub fn show_error_window(ctx: &egui::Context, panic: &GuiPanic, consecutive_panics: u32) -> bool {
+    let mut dismissed = false;
+
+    egui::Window::new("segui: panic in gui callback")
+        .default_width(600.0)
+        .show(ctx, |ui| {
+            ui.colored_label(egui::Color32::RED, &panic.message);
+            if consecutive_panics > 1 {
+                ui.label(format!("{consecutive_panics} frames in a row"));
+            }
+
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    ui.monospace(&panic.backtrace);
+                });
+
+            ui.separator();
+            dismissed = ui.button("Dismiss").clicked();
+        });
+
+    dismissed
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::SokolEgui;
+
+/*
+    NOTE: egui::Memory is stored under this key, everything else is free for the application
+*/
+pub const EGUI_MEMORY_KEY: &str = "egui";
+pub const DEFAULT_AUTOSAVE_INTERVAL: f64 = 30.0;
+
+pub trait Storage {
+    fn get_string(&self, key: &str) -> Option<String>;
+    fn set_string(&mut self, key: &str, value: String);
+
+    /*
+        NOTE: Called after every save, so writes can be batched
+    */
+    fn flush(&mut self);
+}
+
+/*
+    NOTE: Keeps every key in memory and writes all of them to a single ron file on flush()
+*/
+#[derive(Debug, Clone, Default)]
+pub struct FileStorage {
+    pub path: PathBuf,
+    pub values: HashMap<String, String>,
+    pub dirty: bool,
+}
+
+impl FileStorage {
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+
+        let values = match std::fs::read_to_string(&path) {
+            Ok(contents) => match ron::from_str(&contents) {
+                Ok(values) => values,
+                Err(error) => {
+                    println!(
+                        "segui persistence: failed to parse '{}': {error}",
+                        path.display()
+                    );
+                    HashMap::new()
+                }
+            },
+
+            /*
+                NOTE: Nothing saved yet
+            */
+            Err(_) => HashMap::new(),
+        };
+
+        Self {
+            path,
+            values,
+            dirty: false,
+        }
+    }
+}
+
+impl Storage for FileStorage {
+    fn get_string(&self, key: &str) -> Option<String> {
+        self.values.get(key).cloned()
+    }
+
+    fn set_string(&mut self, key: &str, value: String) {
+        if self.values.get(key) != Some(&value) {
+            self.values.insert(key.to_string(), value);
+            self.dirty = true;
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.dirty {
+            return;
+        }
+
+        let contents = match ron::ser::to_string_pretty(&self.values, Default::default()) {
+            Ok(contents) => contents,
+            Err(error) => {
+                println!("segui persistence: failed to serialize: {error}");
+                return;
+            }
+        };
+
+        if let Some(parent) = self.path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        match std::fs::write(&self.path, contents) {
+            Ok(()) => self.dirty = false,
+            Err(error) => {
+                println!(
+                    "segui persistence: failed to write '{}': {error}",
+                    self.path.display()
+                )
+            }
+        }
+    }
+}
+
+impl SokolEgui {
+    /*
+        NOTE: Replaces the current storage and restores the egui memory from it
+    */
+    pub fn set_storage(&mut self, storage: Box<dyn Storage>) {
+        self.storage = Some(storage);
+        self.load_memory();
+    }
+
+    /*
+        NOTE: Keeps the egui memory (window positions, collapsed state, ...) in a file. It is
+              restored right away and saved in shutdown() and every autosave_interval seconds
+    */
+    pub fn set_persistence_path(&mut self, path: impl AsRef<Path>) {
+        self.set_storage(Box::new(FileStorage::load(path)));
+    }
+
+    pub fn storage(&self) -> Option<&dyn Storage> {
+        self.storage.as_deref()
+    }
+
+    pub fn storage_mut(&mut self) -> Option<&mut (dyn Storage + 'static)> {
+        self.storage.as_deref_mut()
+    }
+
+    pub fn load_memory(&mut self) {
+        let Some(storage) = self.storage.as_ref() else {
+            return;
+        };
+        let Some(contents) = storage.get_string(EGUI_MEMORY_KEY) else {
+            return;
+        };
+
+        match ron::from_str::<egui::Memory>(&contents) {
+            Ok(memory) => self
+                .context
+                .memory_mut(|context_memory| *context_memory = memory),
+            Err(error) => println!("segui persistence: failed to restore egui memory: {error}"),
+        }
+    }
+
+    pub fn save(&mut self) {
+        self.last_save = std::time::Instant::now();
+
+        let Some(storage) = self.storage.as_mut() else {
+            return;
+        };
+
+        let memory = self.context.memory(|memory| memory.clone());
+        match ron::to_string(&memory) {
+            Ok(contents) => storage.set_string(EGUI_MEMORY_KEY, contents),
+            Err(error) => println!("segui persistence: failed to serialize egui memory: {error}"),
+        }
+        storage.flush();
+    }
+
+    pub(crate) fn autosave(&mut self) {
+        if self.storage.is_some()
+            && self.last_save.elapsed().as_secs_f64() >= self.autosave_interval
+        {
+            self.save();
+        }
+    }
+}
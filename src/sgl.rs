@@ -0,0 +1,113 @@
+use sokol::gfx as sg;
+use sokol::gl as sgl;
+
+use crate::{egui_blend_state, PipelineDesc, SokolEgui};
+
+/*
+    NOTE: Draws egui through sokol_gl instead of our own shader and buffers. The app has to call
+          sgl::setup() before segui::begin(). egui gets its own sgl context, so that the
+          commands of the app aren't mixed into ours. Meshes are drawn as plain triangles, one
+          sgl vertex per index, and sgl contexts can't grow, so the initial index capacity from
+          the Desc is all egui gets
+*/
+#[derive(Debug, Copy, Clone)]
+pub struct SglRenderer {
+    pub context: sgl::Context,
+    pub pipeline: sgl::Pipeline,
+}
+
+impl SglRenderer {
+    pub fn new(desc: &PipelineDesc, max_vertices: usize) -> Self {
+        let context = sgl::make_context(&sgl::ContextDesc {
+            max_vertices: max_vertices as _,
+            max_commands: (max_vertices / 4) as _,
+            color_format: desc.color_format,
+            depth_format: desc.depth_format,
+            sample_count: desc.sample_count,
+            ..Default::default()
+        });
+
+        let mut pipeline_desc = sg::PipelineDesc::new();
+        pipeline_desc.colors[0].blend = egui_blend_state(desc.blend_mode);
+        let pipeline = sgl::context_make_pipeline(context, &pipeline_desc);
+
+        Self { context, pipeline }
+    }
+
+    pub fn render(&self, state: &SokolEgui, width: i32, height: i32) {
+        let screen_rect = state.context.screen_rect();
+
+        let previous_context = sgl::get_context();
+        sgl::set_context(self.context);
+
+        sgl::defaults();
+        sgl::viewport(0, 0, width, height, true);
+        sgl::load_pipeline(self.pipeline);
+        sgl::matrix_mode_projection();
+        sgl::ortho(
+            0.0,
+            screen_rect.width(),
+            screen_rect.height(),
+            0.0,
+            -1.0,
+            1.0,
+        );
+
+        let scale_x = width as f32 / screen_rect.width();
+        let scale_y = height as f32 / screen_rect.height();
+
+        /*
+            NOTE: sokol_gl only records the commands and draws them all at once at the end, so
+                  there is no point in time where a paint callback could draw
+        */
+        for egui::ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in &state.clipped_primitives
+        {
+            let egui::epaint::Primitive::Mesh(mesh) = primitive else {
+                continue;
+            };
+
+            let Some([x, y, w, h]) =
+                crate::clip_rect_to_pixels(*clip_rect, scale_x, scale_y, width, height)
+            else {
+                continue;
+            };
+            sgl::scissor_rect(x, y, w, h, true);
+
+            match state.textures.image(mesh.texture_id) {
+                Some(image) => {
+                    sgl::enable_texture();
+                    sgl::texture(image);
+                }
+                None => sgl::disable_texture(),
+            }
+
+            sgl::begin_triangles();
+            for index in &mesh.indices {
+                let vertex = &mesh.vertices[*index as usize];
+                let [r, g, b, a] = vertex.color.to_array();
+                sgl::v2f_t2f_c4b(
+                    vertex.pos.x,
+                    vertex.pos.y,
+                    vertex.uv.x,
+                    vertex.uv.y,
+                    r,
+                    g,
+                    b,
+                    a,
+                );
+            }
+            sgl::end();
+        }
+
+        sgl::set_context(previous_context);
+        sgl::context_draw(self.context);
+    }
+
+    pub fn destroy(&self) {
+        sgl::destroy_pipeline(self.pipeline);
+        sgl::destroy_context(self.context);
+    }
+}
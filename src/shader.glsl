@@ -0,0 +1,47 @@
+/*
+    NOTE: src/shader.rs is generated from this file with:
+
+          sokol-shdc -i src/shader.glsl -o src/shader.rs -l glsl330:glsl300es:hlsl4:metal_macos:metal_ios:metal_sim:wgsl -f sokol_rust
+*/
+
+@vs vs
+uniform vs_params {
+    vec2 screen_size;
+};
+
+in vec2 in_position;
+in vec2 in_uv;
+in vec4 in_color;
+
+out vec2 uv;
+out vec4 color;
+
+void main() {
+    gl_Position = vec4(
+        2.0 * in_position.x / screen_size.x - 1.0,
+        1.0 - 2.0 * in_position.y / screen_size.y,
+        0.0,
+        1.0
+    );
+    uv = in_uv;
+    color = in_color;
+}
+@end
+
+@fs fs
+uniform sampler2D tex;
+
+in vec2 uv;
+in vec4 color;
+
+out vec4 frag_color;
+
+void main() {
+    /*
+        NOTE: Both the vertex colors and the textures from egui are premultiplied
+    */
+    frag_color = color * texture(tex, uv);
+}
+@end
+
+@program egui vs fs
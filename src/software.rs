@@ -0,0 +1,542 @@
+use std::collections::HashMap;
+
+/*
+    NOTE: Rasterizes the same tessellated output as draw() on the cpu, for machines without a gpu
+          and for looking at the ui in tests. Nothing in here touches sokol, so it can be used
+          with a plain egui::Context while sapp isn't running.
+
+          Like the gpu path it blends the premultiplied sRGB colors from egui directly, so the
+          result should look the same
+*/
+
+#[derive(Debug, Clone)]
+pub struct SoftwareTexture {
+    pub size: [usize; 2],
+    pub options: egui::TextureOptions,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SoftwareRenderer {
+    pub width: usize,
+    pub height: usize,
+
+    /*
+        NOTE: Premultiplied RGBA8, row by row from the top left corner
+    */
+    pub pixels: Vec<u8>,
+    pub textures: HashMap<egui::TextureId, SoftwareTexture>,
+}
+
+impl SoftwareRenderer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width * height * 4],
+            textures: HashMap::new(),
+        }
+    }
+
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.pixels.resize(width * height * 4, 0);
+    }
+
+    pub fn clear(&mut self, color: egui::Color32) {
+        for pixel in self.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&color.to_array());
+        }
+    }
+
+    pub fn set_texture(&mut self, texture_id: egui::TextureId, delta: &egui::epaint::ImageDelta) {
+        let size = delta.image.size();
+        let pixels: Vec<[u8; 4]> = crate::textures::image_data_to_rgba(&delta.image)
+            .chunks_exact(4)
+            .map(|pixel| [pixel[0], pixel[1], pixel[2], pixel[3]])
+            .collect();
+
+        if let Some([x, y]) = delta.pos {
+            let Some(texture) = self.textures.get_mut(&texture_id) else {
+                println!("segui software: partial update of unknown texture '{texture_id:?}'");
+                return;
+            };
+
+            if x + size[0] > texture.size[0] || y + size[1] > texture.size[1] {
+                println!("segui software: partial update of '{texture_id:?}' is out of bounds");
+                return;
+            }
+
+            for row in 0..size[1] {
+                let dst = (y + row) * texture.size[0] + x;
+                let src = row * size[0];
+                texture.pixels[dst..dst + size[0]].copy_from_slice(&pixels[src..src + size[0]]);
+            }
+            texture.options = delta.options;
+        } else {
+            self.textures.insert(
+                texture_id,
+                SoftwareTexture {
+                    size,
+                    options: delta.options,
+                    pixels,
+                },
+            );
+        }
+    }
+
+    pub fn free_texture(&mut self, texture_id: egui::TextureId) {
+        self.textures.remove(&texture_id);
+    }
+
+    pub fn update_textures(&mut self, textures_delta: &egui::TexturesDelta) {
+        for (texture_id, delta) in &textures_delta.set {
+            self.set_texture(*texture_id, delta);
+        }
+    }
+
+    pub fn free_textures(&mut self, textures_delta: &egui::TexturesDelta) {
+        for texture_id in &textures_delta.free {
+            self.free_texture(*texture_id);
+        }
+    }
+
+    /*
+        NOTE: Convenience for the whole sequence draw() goes through for one egui::FullOutput
+    */
+    pub fn paint(&mut self, context: &egui::Context, output: egui::FullOutput) {
+        self.update_textures(&output.textures_delta);
+
+        let clipped_primitives = context.tessellate(output.shapes);
+        self.render(&clipped_primitives, context.pixels_per_point());
+
+        self.free_textures(&output.textures_delta);
+    }
+
+    pub fn render(&mut self, clipped_primitives: &[egui::ClippedPrimitive], pixels_per_point: f32) {
+        for egui::ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in clipped_primitives
+        {
+            let egui::epaint::Primitive::Mesh(mesh) = primitive else {
+                /*
+                    NOTE: Paint callbacks draw with sokol, so there is nothing we can do here
+                */
+                continue;
+            };
+
+            let clip = [
+                (clip_rect.min.x * pixels_per_point).round().max(0.0) as usize,
+                (clip_rect.min.y * pixels_per_point).round().max(0.0) as usize,
+                ((clip_rect.max.x * pixels_per_point).round().max(0.0) as usize).min(self.width),
+                ((clip_rect.max.y * pixels_per_point).round().max(0.0) as usize).min(self.height),
+            ];
+
+            if clip[0] >= clip[2] || clip[1] >= clip[3] {
+                continue;
+            }
+
+            let texture = self.textures.get(&mesh.texture_id);
+
+            for triangle in mesh.indices.chunks_exact(3) {
+                let vertices = [
+                    mesh.vertices[triangle[0] as usize],
+                    mesh.vertices[triangle[1] as usize],
+                    mesh.vertices[triangle[2] as usize],
+                ];
+
+                rasterize_triangle(
+                    &mut self.pixels,
+                    self.width,
+                    clip,
+                    &vertices,
+                    pixels_per_point,
+                    texture,
+                );
+            }
+        }
+    }
+
+    /*
+        NOTE: Straight alpha RGBA8, which is what image files expect
+    */
+    pub fn to_unmultiplied_rgba(&self) -> Vec<u8> {
+        self.pixels
+            .chunks_exact(4)
+            .flat_map(|pixel| {
+                egui::Color32::from_rgba_premultiplied(pixel[0], pixel[1], pixel[2], pixel[3])
+                    .to_srgba_unmultiplied()
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "png")]
+    pub fn write_png(&self, path: impl AsRef<std::path::Path>) -> Result<(), png::EncodingError> {
+        let file = std::fs::File::create(path)?;
+        let mut encoder = png::Encoder::new(
+            std::io::BufWriter::new(file),
+            self.width as u32,
+            self.height as u32,
+        );
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.to_unmultiplied_rgba())?;
+        writer.finish()
+    }
+}
+
+fn edge_function(a: egui::Pos2, b: egui::Pos2, p: egui::Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/*
+    NOTE: Top-left fill rule, so pixels on edges shared by two triangles are only drawn once
+*/
+fn is_top_left(a: egui::Pos2, b: egui::Pos2) -> bool {
+    let edge = b - a;
+    edge.y > 0.0 || (edge.y == 0.0 && edge.x < 0.0)
+}
+
+fn rasterize_triangle(
+    target: &mut [u8],
+    target_width: usize,
+    clip: [usize; 4],
+    vertices: &[egui::epaint::Vertex; 3],
+    pixels_per_point: f32,
+    texture: Option<&SoftwareTexture>,
+) {
+    let mut p = vertices.map(|vertex| {
+        egui::pos2(
+            vertex.pos.x * pixels_per_point,
+            vertex.pos.y * pixels_per_point,
+        )
+    });
+    let mut v = *vertices;
+
+    let mut area = edge_function(p[0], p[1], p[2]);
+    if area == 0.0 {
+        return;
+    }
+    if area < 0.0 {
+        p.swap(1, 2);
+        v.swap(1, 2);
+        area = -area;
+    }
+
+    let min_x = p[0].x.min(p[1].x).min(p[2].x).floor().max(clip[0] as f32) as usize;
+    let min_y = p[0].y.min(p[1].y).min(p[2].y).floor().max(clip[1] as f32) as usize;
+    let max_x = (p[0].x.max(p[1].x).max(p[2].x).ceil().max(0.0) as usize).min(clip[2]);
+    let max_y = (p[0].y.max(p[1].y).max(p[2].y).ceil().max(0.0) as usize).min(clip[3]);
+
+    let edges = [(p[1], p[2]), (p[2], p[0]), (p[0], p[1])];
+    let top_left = edges.map(|(a, b)| is_top_left(a, b));
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let center = egui::pos2(x as f32 + 0.5, y as f32 + 0.5);
+
+            let mut weights = [0.0; 3];
+            let mut inside = true;
+            for (i, (a, b)) in edges.iter().enumerate() {
+                let w = edge_function(*a, *b, center);
+                if w < 0.0 || (w == 0.0 && !top_left[i]) {
+                    inside = false;
+                    break;
+                }
+                weights[i] = w / area;
+            }
+            if !inside {
+                continue;
+            }
+
+            let uv = v[0].uv.to_vec2() * weights[0]
+                + v[1].uv.to_vec2() * weights[1]
+                + v[2].uv.to_vec2() * weights[2];
+
+            let colors = v.map(|vertex| vertex.color.to_array());
+            let mut color = [0.0; 4];
+            for (channel, value) in color.iter_mut().enumerate() {
+                *value = (colors[0][channel] as f32 * weights[0]
+                    + colors[1][channel] as f32 * weights[1]
+                    + colors[2][channel] as f32 * weights[2])
+                    / 255.0;
+            }
+
+            if let Some(texture) = texture {
+                let texel = sample_texture(texture, uv.x, uv.y);
+                for (value, texel) in color.iter_mut().zip(texel) {
+                    *value *= texel;
+                }
+            }
+
+            let index = (y * target_width + x) * 4;
+            let inverse_alpha = 1.0 - color[3];
+            for (dst, src) in target[index..index + 4].iter_mut().zip(color) {
+                let blended = src + *dst as f32 / 255.0 * inverse_alpha;
+                *dst = (blended * 255.0).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+}
+
+fn sample_texture(texture: &SoftwareTexture, u: f32, v: f32) -> [f32; 4] {
+    let [width, height] = texture.size;
+    if width == 0 || height == 0 {
+        return [1.0; 4];
+    }
+
+    let texel = |x: isize, y: isize| -> [f32; 4] {
+        let x = x.clamp(0, width as isize - 1) as usize;
+        let y = y.clamp(0, height as isize - 1) as usize;
+        texture.pixels[y * width + x].map(|channel| channel as f32 / 255.0)
+    };
+
+    let x = u * width as f32 - 0.5;
+    let y = v * height as f32 - 0.5;
+
+    /*
+        NOTE: There is no mipmapping, so minification is ignored and only magnification is used
+    */
+    match texture.options.magnification {
+        egui::TextureFilter::Nearest => texel(x.round() as isize, y.round() as isize),
+        egui::TextureFilter::Linear => {
+            let x0 = x.floor();
+            let y0 = y.floor();
+            let tx = x - x0;
+            let ty = y - y0;
+
+            let a = texel(x0 as isize, y0 as isize);
+            let b = texel(x0 as isize + 1, y0 as isize);
+            let c = texel(x0 as isize, y0 as isize + 1);
+            let d = texel(x0 as isize + 1, y0 as isize + 1);
+
+            let mut result = [0.0; 4];
+            for (channel, value) in result.iter_mut().enumerate() {
+                let top = a[channel] + (b[channel] - a[channel]) * tx;
+                let bottom = c[channel] + (d[channel] - c[channel]) * tx;
+                *value = top + (bottom - top) * ty;
+            }
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXTURE_ID: egui::TextureId = egui::TextureId::User(1);
+
+    fn rect(min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> egui::Rect {
+        egui::Rect::from_min_max(egui::pos2(min_x, min_y), egui::pos2(max_x, max_y))
+    }
+
+    fn quad(
+        clip_rect: egui::Rect,
+        mesh_rect: egui::Rect,
+        color: egui::Color32,
+        texture_id: egui::TextureId,
+    ) -> egui::ClippedPrimitive {
+        let mut mesh = egui::Mesh::with_texture(texture_id);
+        mesh.add_rect_with_uv(mesh_rect, rect(0.0, 0.0, 1.0, 1.0), color);
+
+        egui::ClippedPrimitive {
+            clip_rect,
+            primitive: egui::epaint::Primitive::Mesh(mesh),
+        }
+    }
+
+    fn set_texture(
+        renderer: &mut SoftwareRenderer,
+        pixels: &[egui::Color32],
+        size: [usize; 2],
+        options: egui::TextureOptions,
+    ) {
+        let image = egui::ColorImage {
+            size,
+            pixels: pixels.to_vec(),
+        };
+        renderer.set_texture(TEXTURE_ID, &egui::epaint::ImageDelta::full(image, options));
+    }
+
+    fn pixel(renderer: &SoftwareRenderer, x: usize, y: usize) -> [u8; 4] {
+        let index = (y * renderer.width + x) * 4;
+        renderer.pixels[index..index + 4].try_into().unwrap()
+    }
+
+    fn assert_pixel(renderer: &SoftwareRenderer, x: usize, y: usize, expected: [u8; 4]) {
+        let actual = pixel(renderer, x, y);
+        let close = actual
+            .iter()
+            .zip(expected)
+            .all(|(actual, expected)| actual.abs_diff(expected) <= 1);
+        assert!(
+            close,
+            "pixel ({x}, {y}) is {actual:?}, expected {expected:?}"
+        );
+    }
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const CLEAR: [u8; 4] = [0, 0, 0, 0];
+
+    #[test]
+    fn fills_the_pixels_covered_by_a_mesh() {
+        let mut renderer = SoftwareRenderer::new(4, 4);
+        renderer.render(
+            &[quad(
+                rect(0.0, 0.0, 4.0, 4.0),
+                rect(1.0, 1.0, 3.0, 3.0),
+                egui::Color32::RED,
+                TEXTURE_ID,
+            )],
+            1.0,
+        );
+
+        for y in 0..4 {
+            for x in 0..4 {
+                let inside = (1..3).contains(&x) && (1..3).contains(&y);
+                assert_pixel(&renderer, x, y, if inside { RED } else { CLEAR });
+            }
+        }
+    }
+
+    #[test]
+    fn clips_to_the_clip_rect_in_pixels() {
+        let mut renderer = SoftwareRenderer::new(4, 4);
+        renderer.render(
+            &[quad(
+                rect(0.0, 0.5, 1.0, 2.0),
+                rect(0.0, 0.0, 2.0, 2.0),
+                egui::Color32::RED,
+                TEXTURE_ID,
+            )],
+            2.0,
+        );
+
+        for y in 0..4 {
+            for x in 0..4 {
+                let inside = x < 2 && y >= 1;
+                assert_pixel(&renderer, x, y, if inside { RED } else { CLEAR });
+            }
+        }
+    }
+
+    #[test]
+    fn samples_nearest() {
+        let mut renderer = SoftwareRenderer::new(4, 1);
+        set_texture(
+            &mut renderer,
+            &[egui::Color32::RED, egui::Color32::BLUE],
+            [2, 1],
+            egui::TextureOptions::NEAREST,
+        );
+        renderer.render(
+            &[quad(
+                rect(0.0, 0.0, 4.0, 1.0),
+                rect(0.0, 0.0, 4.0, 1.0),
+                egui::Color32::WHITE,
+                TEXTURE_ID,
+            )],
+            1.0,
+        );
+
+        assert_pixel(&renderer, 0, 0, RED);
+        assert_pixel(&renderer, 1, 0, RED);
+        assert_pixel(&renderer, 2, 0, BLUE);
+        assert_pixel(&renderer, 3, 0, BLUE);
+    }
+
+    #[test]
+    fn samples_linear() {
+        let mut renderer = SoftwareRenderer::new(4, 1);
+        set_texture(
+            &mut renderer,
+            &[egui::Color32::RED, egui::Color32::BLUE],
+            [2, 1],
+            egui::TextureOptions::LINEAR,
+        );
+        renderer.render(
+            &[quad(
+                rect(0.0, 0.0, 4.0, 1.0),
+                rect(0.0, 0.0, 4.0, 1.0),
+                egui::Color32::WHITE,
+                TEXTURE_ID,
+            )],
+            1.0,
+        );
+
+        /*
+            NOTE: The outer pixels are clamped to the edge texels, the inner ones are 1/4 and 3/4
+                  of the way between them
+        */
+        assert_pixel(&renderer, 0, 0, RED);
+        assert_pixel(&renderer, 1, 0, [191, 0, 64, 255]);
+        assert_pixel(&renderer, 2, 0, [64, 0, 191, 255]);
+        assert_pixel(&renderer, 3, 0, BLUE);
+    }
+
+    #[test]
+    fn blends_premultiplied_colors() {
+        let mut renderer = SoftwareRenderer::new(1, 1);
+        renderer.clear(egui::Color32::BLUE);
+        renderer.render(
+            &[quad(
+                rect(0.0, 0.0, 1.0, 1.0),
+                rect(0.0, 0.0, 1.0, 1.0),
+                egui::Color32::from_rgba_premultiplied(128, 0, 0, 128),
+                TEXTURE_ID,
+            )],
+            1.0,
+        );
+
+        assert_pixel(&renderer, 0, 0, [128, 0, 127, 255]);
+    }
+
+    #[test]
+    fn updates_part_of_a_texture() {
+        let mut renderer = SoftwareRenderer::new(1, 1);
+        set_texture(
+            &mut renderer,
+            &[egui::Color32::RED; 4],
+            [2, 2],
+            egui::TextureOptions::NEAREST,
+        );
+
+        let column = egui::ColorImage::new([1, 2], egui::Color32::GREEN);
+        renderer.set_texture(
+            TEXTURE_ID,
+            &egui::epaint::ImageDelta::partial([1, 0], column, egui::TextureOptions::LINEAR),
+        );
+
+        let texture = &renderer.textures[&TEXTURE_ID];
+        assert_eq!(texture.size, [2, 2]);
+        assert_eq!(texture.options, egui::TextureOptions::LINEAR);
+        assert_eq!(
+            texture.pixels,
+            [
+                egui::Color32::RED.to_array(),
+                egui::Color32::GREEN.to_array(),
+                egui::Color32::RED.to_array(),
+                egui::Color32::GREEN.to_array(),
+            ]
+        );
+
+        /*
+            NOTE: Updates that don't fit are dropped instead of writing out of bounds
+        */
+        let too_wide = egui::ColorImage::new([2, 1], egui::Color32::BLUE);
+        renderer.set_texture(
+            TEXTURE_ID,
+            &egui::epaint::ImageDelta::partial([1, 1], too_wide, egui::TextureOptions::NEAREST),
+        );
+        assert_eq!(
+            renderer.textures[&TEXTURE_ID].pixels[3],
+            egui::Color32::GREEN.to_array()
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use sokol::gfx as sg;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct Texture {
+    pub image: sg::Image,
+    pub size: [usize; 2],
+    pub options: egui::TextureOptions,
+
+    /*
+        NOTE: sokol-gfx can only replace the whole content of an image and only once per frame,
+              so we keep a copy of the pixels around to apply partial updates to
+    */
+    pub pixels: Vec<u8>,
+    pub dirty: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Textures {
+    pub managed: HashMap<u64, Texture>,
+
+    /*
+        NOTE: User images are owned by the application, we only keep the handle around and never
+              destroy them. Filtering and wrapping is part of the sg::Image itself, so it is
+              whatever the application put in its sg::ImageDesc
+    */
+    pub user: HashMap<u64, sg::Image>,
+    pub next_user_id: u64,
+}
+
+impl Textures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn image(&self, texture_id: egui::TextureId) -> Option<sg::Image> {
+        match texture_id {
+            egui::TextureId::Managed(id) => self.managed.get(&id).map(|texture| texture.image),
+            egui::TextureId::User(id) => self.user.get(&id).copied(),
+        }
+    }
+
+    pub fn register_user(&mut self, image: sg::Image) -> egui::TextureId {
+        let id = self.next_user_id;
+        self.next_user_id += 1;
+        self.user.insert(id, image);
+        egui::TextureId::User(id)
+    }
+
+    pub fn replace_user(&mut self, texture_id: egui::TextureId, image: sg::Image) -> bool {
+        match texture_id {
+            egui::TextureId::User(id) => match self.user.get_mut(&id) {
+                Some(user_image) => {
+                    *user_image = image;
+                    true
+                }
+                None => false,
+            },
+            egui::TextureId::Managed(_) => false,
+        }
+    }
+
+    pub fn unregister_user(&mut self, texture_id: egui::TextureId) -> Option<sg::Image> {
+        match texture_id {
+            egui::TextureId::User(id) => self.user.remove(&id),
+            egui::TextureId::Managed(_) => None,
+        }
+    }
+
+    pub fn set(&mut self, texture_id: egui::TextureId, delta: &egui::epaint::ImageDelta) {
+        let egui::TextureId::Managed(id) = texture_id else {
+            println!("segui textures: egui tried to set user texture '{texture_id:?}'");
+            return;
+        };
+
+        let size = delta.image.size();
+        let pixels = image_data_to_rgba(&delta.image);
+
+        if let Some([x, y]) = delta.pos {
+            let Some(texture) = self.managed.get_mut(&id) else {
+                println!("segui textures: partial update of unknown texture '{texture_id:?}'");
+                return;
+            };
+
+            if x + size[0] > texture.size[0] || y + size[1] > texture.size[1] {
+                println!("segui textures: partial update of '{texture_id:?}' is out of bounds");
+                return;
+            }
+
+            let row_length = size[0] * 4;
+            for row in 0..size[1] {
+                let dst = ((y + row) * texture.size[0] + x) * 4;
+                let src = row * row_length;
+                texture.pixels[dst..dst + row_length]
+                    .copy_from_slice(&pixels[src..src + row_length]);
+            }
+            texture.dirty = true;
+
+            if texture.options != delta.options {
+                texture.options = delta.options;
+                recreate_image(texture);
+            }
+        } else {
+            match self.managed.get_mut(&id) {
+                Some(texture) if texture.size == size && texture.options == delta.options => {
+                    texture.pixels = pixels;
+                    texture.dirty = true;
+                }
+
+                _ => {
+                    /*
+                        NOTE: The font atlas grows by sending a full image of a new size
+                    */
+                    let mut texture = Texture {
+                        image: sg::Image::new(),
+                        size,
+                        options: delta.options,
+                        pixels,
+                        dirty: true,
+                    };
+                    recreate_image(&mut texture);
+
+                    if let Some(old_texture) = self.managed.insert(id, texture) {
+                        sg::destroy_image(old_texture.image);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn upload_dirty(&mut self) {
+        for texture in self.managed.values_mut().filter(|texture| texture.dirty) {
+            let mut data = sg::ImageData::new();
+            data.subimage[0][0] = sg::slice_as_range(&texture.pixels);
+            sg::update_image(texture.image, &data);
+            texture.dirty = false;
+        }
+    }
+
+    pub fn free(&mut self, texture_id: egui::TextureId) {
+        if let egui::TextureId::Managed(id) = texture_id {
+            if let Some(texture) = self.managed.remove(&id) {
+                sg::destroy_image(texture.image);
+            }
+        }
+    }
+
+    pub fn destroy_all(&mut self) {
+        for (_, texture) in self.managed.drain() {
+            sg::destroy_image(texture.image);
+        }
+        self.user.clear();
+    }
+}
+
+fn recreate_image(texture: &mut Texture) {
+    if texture.image.id != 0 {
+        sg::destroy_image(texture.image);
+    }
+
+    texture.image = sg::make_image(&sg::ImageDesc {
+        width: texture.size[0] as _,
+        height: texture.size[1] as _,
+        pixel_format: sg::PixelFormat::Rgba8,
+        usage: sg::Usage::Dynamic,
+        min_filter: egui_filter_to_sg_filter(texture.options.minification),
+        mag_filter: egui_filter_to_sg_filter(texture.options.magnification),
+        wrap_u: sg::Wrap::ClampToEdge,
+        wrap_v: sg::Wrap::ClampToEdge,
+        label: b"segui-texture\0".as_ptr() as _,
+        ..Default::default()
+    });
+    texture.dirty = true;
+}
+
+pub fn image_data_to_rgba(image: &egui::ImageData) -> Vec<u8> {
+    match image {
+        egui::ImageData::Color(image) => image
+            .pixels
+            .iter()
+            .flat_map(|color| color.to_array())
+            .collect(),
+        egui::ImageData::Font(image) => image
+            .srgba_pixels(None)
+            .flat_map(|color| color.to_array())
+            .collect(),
+    }
+}
+
+#[rustfmt::skip]
+pub fn egui_filter_to_sg_filter(filter: egui::TextureFilter) -> sg::Filter {
+    match filter {
+        egui::TextureFilter::Nearest => sg::Filter::Nearest,
+        egui::TextureFilter::Linear  => sg::Filter::Linear,
+    }
+}